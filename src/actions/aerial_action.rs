@@ -0,0 +1,253 @@
+use rlbot_lib::rlbot::{ControllerState, PredictionSlice, RenderMessage, Vector3};
+
+use crate::utils::{
+    math::math::{forward_vec, up_vec, vec_new, Vec3},
+    physics::physics::{
+        jump_height, BALL_RADIUS, BOOST_ACCEL, BOOST_PER_SECOND, CAR_REST_HEIGHT, GRAVITY,
+        JUMP_HOLD_TIME,
+    },
+    render::render::{cross, GREEN},
+    world::world::{Ball, Car, World},
+    ActionTickResult,
+};
+
+use super::{
+    action::{Action, ActionResult, FailureReason},
+    jump_action::JumpAction,
+    reorient_action::ReorientAction,
+};
+
+/// Don't plan aerials further out than this, the prediction isn't worth much by then
+const MAX_AERIAL_TIME: f32 = 4.;
+/// Balls lower than this are for driving or jumping into
+const MIN_AERIAL_HEIGHT: f32 = 200.;
+/// Roughly how fast we can swing the nose around in the air, in radians per second
+const AIR_TURN_RATE: f32 = 3.;
+/// Plan on at most this much of the boost's acceleration, so there's some left for corrections
+const PLANNED_ACCEL: f32 = 0.8;
+/// Boost whenever we'd need more than this much of the boost's acceleration to get there, and
+/// coast otherwise
+const BOOST_THRESHOLD: f32 = 0.4;
+/// How late we can be before giving up on the ball
+const MISS_SLACK: f32 = 0.2;
+
+/// Where the car would be in `time` seconds if it stopped trying, jumping first if it's on the
+/// ground
+fn ballistic(car: &Car, time: f32) -> Vector3 {
+    let drop = if car.has_wheel_contact {
+        jump_height(time, false)
+    } else {
+        0.5 * GRAVITY * time * time
+    };
+    let mut location = car.location.add(&car.velocity.scale(time)).add(&vec_new(0., 0., drop));
+    // it lands instead of falling through the floor
+    location.z = location.z.max(CAR_REST_HEIGHT);
+    location
+}
+
+/// Jumps if it has to, then boosts up to meet the ball in the air
+#[derive(Clone, Debug)]
+pub struct AerialAction {
+    car_id: usize,
+    /// Where we're meeting the ball, picked from the predictions on our first step unless it's
+    /// given up front
+    pub target: Option<Vector3>,
+    /// secondsElapsed when we should be at `target`
+    pub arrival: f32,
+    jump: Option<JumpAction>,
+    /// secondsElapsed on our first step
+    start_time: Option<f32>,
+    /// distance left to the target as of the last step
+    remaining_dist: Option<f32>,
+}
+
+impl AerialAction {
+    pub fn new(car_id: usize) -> AerialAction {
+        AerialAction {
+            car_id,
+            target: None,
+            arrival: f32::MAX,
+            jump: None,
+            start_time: None,
+            remaining_dist: None,
+        }
+    }
+
+    /// Meet the ball at `target` at `arrival`, instead of looking for somewhere ourselves
+    pub fn with_target(mut self, target: Vector3, arrival: f32) -> AerialAction {
+        self.target = Some(target);
+        self.arrival = arrival;
+        self
+    }
+
+    /// Whether boosting at a constant rate gets the car to `target` in `time` seconds, with the
+    /// boost it has, after turning its nose the way it needs to go
+    pub fn reachable(car: &Car, target: &Vector3, time: f32) -> bool {
+        if time < 0.2 || time > MAX_AERIAL_TIME {
+            return false;
+        }
+        let needed = target.sub(&ballistic(car, time));
+        let turn_time = if needed.norm() > 1. {
+            forward_vec(&car.rotation).angle_between(&needed) / AIR_TURN_RATE
+        } else {
+            0.
+        };
+        let boost_time = time - turn_time;
+        if boost_time <= 0. {
+            return false;
+        }
+        let accel = 2. * needed.norm() / (boost_time * boost_time);
+        let boost_needed = accel / BOOST_ACCEL * boost_time * BOOST_PER_SECOND;
+        accel < BOOST_ACCEL * PLANNED_ACCEL && boost_needed < car.boost as f32
+    }
+
+    /// The first ball in the air we can get to, and when it's there
+    pub fn find(
+        car: &Car,
+        game_time: f32,
+        predictions: &Vec<PredictionSlice>,
+    ) -> Option<(Vector3, f32)> {
+        predictions.iter().find_map(|slice| {
+            let ball = slice.physics.as_ref().and_then(|p| Ball::from_physics(p))?;
+            let time_left = slice.gameSeconds - game_time;
+            if ball.location.z > MIN_AERIAL_HEIGHT
+                && AerialAction::reachable(car, &ball.location, time_left)
+            {
+                Some((ball.location, slice.gameSeconds))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl Action for AerialAction {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        let game_time = world.game.seconds_elapsed;
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return ActionResult::Failed(FailureReason::CarMissing),
+        };
+        if self.start_time.is_none() {
+            self.start_time = Some(game_time);
+            if car.has_wheel_contact {
+                self.jump = Some(JumpAction::new(JUMP_HOLD_TIME));
+            }
+        }
+        let start_time = self.start_time.unwrap();
+
+        // we got a touch in, that's all we were here for
+        if let Some(touch) = world.ball.latest_touch.as_ref() {
+            if touch.player_index == self.car_id && touch.game_seconds >= start_time {
+                return ActionResult::Success;
+            }
+        }
+
+        if self.target.is_none() {
+            match AerialAction::find(car, game_time, predictions) {
+                Some((target, arrival)) => {
+                    self.target = Some(target);
+                    self.arrival = arrival;
+                }
+                None => return ActionResult::Failed(FailureReason::TargetUnreachable),
+            }
+        }
+        let target = self.target.clone().unwrap();
+
+        if let Some(jump) = self.jump.as_mut() {
+            match jump.step(world, controller.clone(), predictions, dt) {
+                ActionResult::InProgress(res) => return ActionResult::InProgress(res),
+                _ => self.jump = None,
+            }
+        } else if car.has_wheel_contact {
+            return ActionResult::Failed(FailureReason::Landed);
+        }
+
+        let time_left = self.arrival - game_time;
+        if time_left < -MISS_SLACK {
+            return ActionResult::Failed(FailureReason::TimedOut);
+        }
+        let time = time_left.max(dt);
+        let needed = target.sub(&ballistic(car, time));
+        self.remaining_dist = Some(car.location.dist(&target));
+
+        // give up as soon as there's no way of making it, rather than flying around until we land.
+        // Close enough to hit the ball doesn't count, the numbers blow up right at the end
+        let accel = 2. * needed.norm() / (time * time);
+        if needed.norm() > BALL_RADIUS {
+            if accel > BOOST_ACCEL {
+                return ActionResult::Failed(FailureReason::TimedOut);
+            }
+            if accel / BOOST_ACCEL * time * BOOST_PER_SECOND > car.boost as f32 {
+                return ActionResult::Failed(FailureReason::OutOfBoost);
+            }
+        }
+
+        // point the nose, and so the boost, where we need to go. Once we're as good as there,
+        // just hold the way we're facing
+        let forward = forward_vec(&car.rotation);
+        let direction = if needed.norm() > 1. {
+            needed.normalize()
+        } else {
+            forward.clone()
+        };
+        // keep the roof where it is, minus whatever part of it lies along the nose, so the two
+        // are never parallel. That's usually straight up under the ball
+        let up = up_vec(&car.rotation);
+        let mut roof = up.sub(&direction.scale(up.dot(&direction)));
+        if roof.norm() < 1e-3 {
+            // roof already pointing where we're going, the old nose is square to it
+            roof = forward.sub(&direction.scale(forward.dot(&direction)));
+        }
+        let mut controller = controller;
+        let mut reorient = ReorientAction::from_uf(roof.normalize(), direction.clone(), self.car_id);
+        if let ActionResult::InProgress(res) =
+            reorient.step(world, controller.clone(), predictions, dt)
+        {
+            controller = res.controller;
+        }
+        controller.boost =
+            accel > BOOST_ACCEL * BOOST_THRESHOLD && forward.angle_between(&direction) < 0.3;
+        ActionResult::InProgress(ActionTickResult::from(controller))
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        match self.target.as_ref() {
+            Some(target) => cross(target, 100., GREEN),
+            None => vec![],
+        }
+    }
+
+    fn interruptible(&self) -> bool {
+        false
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    /// Until the ball gets to the target, plus the slack we give ourselves for hitting it late
+    fn expected_duration(&self) -> Option<f32> {
+        match self.start_time {
+            Some(start) if self.target.is_some() => Some(self.arrival - start + MISS_SLACK),
+            _ => None,
+        }
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.remaining_dist
+    }
+
+    fn name(&self) -> String {
+        match self.target.as_ref() {
+            Some(_) => format!("AerialAction (at {:.2})", self.arrival),
+            None => String::from("AerialAction"),
+        }
+    }
+}
@@ -1,72 +1,93 @@
-use rlbot_lib::rlbot::{ControllerState, GameTickPacket, RenderMessage, Vector3, PredictionSlice};
+use rlbot_lib::rlbot::{ControllerState, RenderMessage, Vector3, PredictionSlice};
 
 use crate::{
     utils::{
-        math::math::{abs_clamp, forward_vec, Vec3},
+        math::math::{abs_clamp, forward_vec, left_vec, up_vec, Vec3},
+        physics::physics::DODGE_WINDOW,
         render::render::{line, YELLOW},
+        world::world::{Car, World},
         ActionTickResult,
     },
-    DEFAULT_CAR_ID,
 };
 
 use super::{
-    action::{Action, ActionResult},
+    action::{Action, ActionResult, FailureReason},
     jump_action::JumpAction,
 };
 
+#[derive(Clone, Debug)]
 pub struct AirDodgeAction {
+    car_id: usize,
     pub duration: f32,
     pub target: Option<Vector3>,
     pub jump: JumpAction,
     pub jump_finished: bool,
     pub counter: i32,
     pub state_timer: f32,
+    /// seconds since we first pressed jump
+    since_jump: f32,
     car_location: Option<Vector3>,
 }
 
 impl AirDodgeAction {
-    pub fn new(duration: f32, target: Option<Vector3>) -> AirDodgeAction {
+    pub fn new(car_id: usize, duration: f32, target: Option<Vector3>) -> AirDodgeAction {
         AirDodgeAction {
+            car_id,
             duration,
             target,
             jump: JumpAction::new(duration),
             jump_finished: false,
             counter: 0,
             state_timer: 0.,
+            since_jump: 0.,
             car_location: None,
         }
     }
+
+    /// Pitch and yaw that dodge towards `target`. Worked out in the car's own frame, so it
+    /// doesn't matter which way the car is facing
+    pub fn stick(car: &Car, target: &Vector3) -> (f32, f32) {
+        let to_target = target.sub(&car.location);
+        let forward = forward_vec(&car.rotation);
+        let local_x = to_target.dot(&forward);
+        let local_y = -to_target.dot(&left_vec(&car.rotation));
+
+        // push the stick as far as it goes, the dodge only cares about the direction
+        let scale = local_x.abs().max(local_y.abs()).max(1e-6);
+        let mut pitch = -local_x / scale;
+        // yaw is backwards when we're upside down
+        let mut yaw = local_y / scale * up_vec(&car.rotation).z.signum();
+
+        if local_x > 0. && car.velocity.dot(&forward) > 500. {
+            // we're already going forwards, so put more into going sideways
+            pitch *= 0.8;
+            yaw = abs_clamp(yaw * 5., 1.);
+        }
+        (pitch, yaw)
+    }
 }
 
-// This is blocked on basic drive and flip actions
 impl Action for AirDodgeAction {
     fn step(
         &mut self,
-        tick_packet: GameTickPacket,
+        world: &World,
         controller: ControllerState,
         predictions: &Vec<PredictionSlice>,
         dt: f32,
     ) -> ActionResult {
-        let car = tick_packet
-            .clone()
-            .players
-            .unwrap()
-            .get(DEFAULT_CAR_ID)
-            .unwrap()
-            .physics
-            .clone()
-            .unwrap();
-        let car_location = car.location.clone().unwrap();
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return ActionResult::Failed(FailureReason::CarMissing),
+        };
+        let car_location = car.location.clone();
         self.car_location = Some(car_location.clone());
-        let rotation = car.rotation.clone().unwrap();
-        let velocity = car.velocity.clone().unwrap();
 
-        // recovery_time = 0.0 if (self.target is None) else 0.4
         let recovery_time = if self.target.is_none() { 0. } else { 0.4 };
 
         let mut controller = controller.clone();
+        self.since_jump += dt;
         if !self.jump_finished {
-            match self.jump.step(tick_packet.clone(), controller.clone(), predictions, dt) {
+            match self.jump.step(world, controller.clone(), predictions, dt) {
                 ActionResult::InProgress(ctrl) => {
                     controller = ctrl.controller;
                 }
@@ -76,40 +97,30 @@ impl Action for AirDodgeAction {
             };
             return ActionResult::InProgress(ActionTickResult::from(controller.to_owned()));
         } else {
-            if self.counter == 0 {
-                if self.target.is_none() {
-                    println!("double jump");
+            // hold the stick until the second jump's gone in, it decides which way we dodge
+            if self.counter < 4 {
+                match self.target.as_ref() {
                     // double jump
-                    controller.roll = 0.;
-                    controller.pitch = 0.;
-                    controller.yaw = 0.;
-                } else {
-                    let target = self.target.clone().unwrap();
+                    None => {
+                        controller.roll = 0.;
+                        controller.pitch = 0.;
+                        controller.yaw = 0.;
+                    }
                     // air dodge
-                    let to_target = car_location.sub(&target).normalize();
-                    println!("to_target: {to_target:?}");
-
-                    // self.controls.roll = 0
-                    // self.controls.pitch = -target_direction[0]
-                    controller.roll = 0.;
-                    controller.pitch = -1.;
-                    controller.boost = false;
-
-                    // NOTE: this line is probably horribly wrong
-                    // controller.yaw = (to_target.x / to_target.y).atan();
-                    controller.yaw = 0.;
-                    println!("yaw: {}", controller.yaw);
-                    // self.controls.yaw = clamp11(sgn(self.car.orientation[2, 2]) * target_direction[1])
-
-                    if to_target.x > 0. && velocity.dot(&forward_vec(&rotation)) > 500. {
-                        controller.pitch *= 0.8;
-                        controller.yaw = abs_clamp(controller.yaw * 5., 1.);
+                    Some(target) => {
+                        let (pitch, yaw) = AirDodgeAction::stick(car, target);
+                        controller.roll = 0.;
+                        controller.pitch = pitch;
+                        controller.yaw = yaw;
+                        controller.boost = false;
                     }
-                    // if target_local[0] > 0 and dot(self.car.velocity, self.car.forward()) > 500:
-                    //    self.controls.pitch = self.controls.pitch * 0.8
-                    //    self.controls.yaw = clamp11(self.controls.yaw * 5)
                 }
-            } else if self.counter == 2 {
+            }
+            if self.counter == 2 {
+                // too late, the second jump wouldn't do anything
+                if self.since_jump > DODGE_WINDOW {
+                    return ActionResult::Failed(FailureReason::TimedOut);
+                }
                 controller.jump = true;
             } else if self.counter >= 4 {
                 controller.roll = 0.;
@@ -121,9 +132,7 @@ impl Action for AirDodgeAction {
             self.state_timer += dt;
         };
 
-        // self.finished = self.jump.finished and self.state_timer > recovery_time and self.counter >= 6
         if self.jump_finished && self.state_timer > recovery_time && self.counter >= 6 {
-            println!("finished jump");
             return ActionResult::Success;
         }
 
@@ -0,0 +1,773 @@
+// Behavior tree nodes. Each one is an Action made out of other actions, so a maneuver can be
+// built by composing the actions we already have instead of writing another phase counter.
+
+use rlbot_lib::rlbot::{ControllerState, PredictionSlice, RenderMessage};
+
+use crate::utils::{channels::channels::overlay, world::world::World, ActionTickResult};
+
+use super::action::{Action, ActionResult, FailureReason, PreemptCause, SharedFn};
+
+/// An action inside a node. Takes care of calling the child's hooks as it's stepped
+#[derive(Clone, Debug)]
+struct Child {
+    action: Box<dyn Action>,
+    started: bool,
+    /// seconds since the child started
+    elapsed: f32,
+}
+
+impl Child {
+    fn new(action: Box<dyn Action>) -> Child {
+        Child {
+            action,
+            started: false,
+            elapsed: 0.,
+        }
+    }
+
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        if !self.started {
+            self.action.on_start(world);
+            self.started = true;
+        }
+        self.elapsed += dt;
+        let result = self.action.step(world, controller, predictions, dt);
+        match &result {
+            ActionResult::Success => self.action.on_finish(Ok(())),
+            ActionResult::Failed(reason) => self.action.on_finish(Err(*reason)),
+            ActionResult::InProgress(_) => {}
+        }
+        result
+    }
+
+    /// End the child early, if it ever started
+    fn stop(&mut self, result: Result<(), FailureReason>) {
+        if self.started {
+            self.action.on_finish(result);
+        }
+    }
+
+    /// The child's expected duration, counted from when its parent started `parent_elapsed`
+    /// seconds ago instead of from when the child did
+    fn expected_duration(&self, parent_elapsed: f32) -> Option<f32> {
+        self.action
+            .expected_duration()
+            .map(|duration| duration + parent_elapsed - self.elapsed)
+    }
+
+    fn interrupt(&mut self, cause: PreemptCause) {
+        if self.started {
+            self.action.on_interrupt(cause);
+        }
+        self.stop(Err(FailureReason::Preempted(cause)));
+    }
+}
+
+fn children(actions: Vec<Box<dyn Action>>) -> Vec<Child> {
+    actions.into_iter().map(Child::new).collect()
+}
+
+/// Runs its children one after another. Fails as soon as one of them fails
+#[derive(Clone, Debug)]
+pub struct Sequence {
+    children: Vec<Child>,
+    current: usize,
+    elapsed: f32,
+}
+
+impl Sequence {
+    pub fn new(actions: Vec<Box<dyn Action>>) -> Sequence {
+        Sequence {
+            children: children(actions),
+            current: 0,
+            elapsed: 0.,
+        }
+    }
+}
+
+impl Action for Sequence {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        self.elapsed += dt;
+        // start the next child on the same tick the last one finished, so we don't lose a tick
+        while let Some(child) = self.children.get_mut(self.current) {
+            match child.step(world, controller.clone(), predictions, dt) {
+                ActionResult::Success => self.current += 1,
+                result => return result,
+            }
+        }
+        ActionResult::Success
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        match self.children.get(self.current) {
+            Some(child) => child.action.render(),
+            None => vec![],
+        }
+    }
+
+    fn interruptible(&self) -> bool {
+        match self.children.get(self.current) {
+            Some(child) => child.action.interruptible(),
+            None => true,
+        }
+    }
+
+    fn kickoff(&self) -> bool {
+        match self.children.get(self.current) {
+            Some(child) => child.action.kickoff(),
+            None => false,
+        }
+    }
+
+    fn name(&self) -> String {
+        match self.children.get(self.current) {
+            Some(child) => format!("Sequence > {}", child.action.name()),
+            None => String::from("Sequence"),
+        }
+    }
+
+    /// The running child's, pushed back by however long the children before it took
+    fn expected_duration(&self) -> Option<f32> {
+        self.children
+            .get(self.current)
+            .and_then(|child| child.expected_duration(self.elapsed))
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.children
+            .get(self.current)
+            .and_then(|child| child.action.progress())
+    }
+
+    fn on_interrupt(&mut self, cause: PreemptCause) {
+        if let Some(child) = self.children.get_mut(self.current) {
+            child.interrupt(cause);
+        }
+    }
+}
+
+/// Tries its children one after another until one of them works. Only fails if they all do
+#[derive(Clone, Debug)]
+pub struct Selector {
+    children: Vec<Child>,
+    current: usize,
+    last_failure: Option<FailureReason>,
+    elapsed: f32,
+}
+
+impl Selector {
+    pub fn new(actions: Vec<Box<dyn Action>>) -> Selector {
+        Selector {
+            children: children(actions),
+            current: 0,
+            last_failure: None,
+            elapsed: 0.,
+        }
+    }
+}
+
+impl Action for Selector {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        self.elapsed += dt;
+        while let Some(child) = self.children.get_mut(self.current) {
+            match child.step(world, controller.clone(), predictions, dt) {
+                ActionResult::Failed(reason) => {
+                    self.last_failure = Some(reason);
+                    self.current += 1;
+                }
+                result => return result,
+            }
+        }
+        ActionResult::Failed(self.last_failure.unwrap_or(FailureReason::ConditionFailed))
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        match self.children.get(self.current) {
+            Some(child) => child.action.render(),
+            None => vec![],
+        }
+    }
+
+    fn interruptible(&self) -> bool {
+        match self.children.get(self.current) {
+            Some(child) => child.action.interruptible(),
+            None => true,
+        }
+    }
+
+    fn kickoff(&self) -> bool {
+        match self.children.get(self.current) {
+            Some(child) => child.action.kickoff(),
+            None => false,
+        }
+    }
+
+    fn name(&self) -> String {
+        match self.children.get(self.current) {
+            Some(child) => format!("Selector > {}", child.action.name()),
+            None => String::from("Selector"),
+        }
+    }
+
+    /// The running child's, pushed back by however long the ones that failed before it took
+    fn expected_duration(&self) -> Option<f32> {
+        self.children
+            .get(self.current)
+            .and_then(|child| child.expected_duration(self.elapsed))
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.children
+            .get(self.current)
+            .and_then(|child| child.action.progress())
+    }
+
+    fn on_interrupt(&mut self, cause: PreemptCause) {
+        if let Some(child) = self.children.get_mut(self.current) {
+            child.interrupt(cause);
+        }
+    }
+}
+
+/// Runs all of its children every tick. Each child gets the controller the one before it
+/// produced, so later children win when they touch the same inputs. Fails as soon as any child
+/// fails
+#[derive(Clone, Debug)]
+pub struct Parallel {
+    children: Vec<Child>,
+    done: Vec<bool>,
+    /// Finish when any child succeeds, instead of waiting for all of them
+    pub succeed_on_any: bool,
+}
+
+impl Parallel {
+    /// Succeeds once every child has
+    pub fn all(actions: Vec<Box<dyn Action>>) -> Parallel {
+        Parallel {
+            done: vec![false; actions.len()],
+            children: children(actions),
+            succeed_on_any: false,
+        }
+    }
+
+    /// Succeeds as soon as one child does, like "drive until we're fast enough"
+    pub fn any(actions: Vec<Box<dyn Action>>) -> Parallel {
+        Parallel {
+            succeed_on_any: true,
+            ..Parallel::all(actions)
+        }
+    }
+
+    /// Wrap up every child that's still going
+    fn stop_running(&mut self, result: Result<(), FailureReason>) {
+        for (child, done) in self.children.iter_mut().zip(self.done.iter()) {
+            if !done {
+                child.stop(result);
+            }
+        }
+    }
+}
+
+impl Action for Parallel {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        let mut action_result = ActionTickResult::from(controller);
+        for i in 0..self.children.len() {
+            if self.done[i] {
+                continue;
+            }
+            let controller = action_result.controller.clone();
+            match self.children[i].step(world, controller, predictions, dt) {
+                ActionResult::InProgress(mut res) => {
+                    action_result.controller = res.controller;
+                    action_result.render.append(&mut res.render);
+                }
+                ActionResult::Success => {
+                    self.done[i] = true;
+                    if self.succeed_on_any {
+                        self.stop_running(Ok(()));
+                        return ActionResult::Success;
+                    }
+                }
+                ActionResult::Failed(reason) => {
+                    self.done[i] = true;
+                    self.stop_running(Err(reason));
+                    return ActionResult::Failed(reason);
+                }
+            }
+        }
+        if self.done.iter().all(|done| *done) {
+            ActionResult::Success
+        } else {
+            ActionResult::InProgress(action_result)
+        }
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        self.children
+            .iter()
+            .flat_map(|child| child.action.render())
+            .collect()
+    }
+
+    fn interruptible(&self) -> bool {
+        self.children
+            .iter()
+            .zip(self.done.iter())
+            .all(|(child, done)| *done || child.action.interruptible())
+    }
+
+    fn kickoff(&self) -> bool {
+        self.children.iter().any(|child| child.action.kickoff())
+    }
+
+    fn name(&self) -> String {
+        let running: Vec<String> = self
+            .children
+            .iter()
+            .zip(self.done.iter())
+            .filter(|(_, done)| !**done)
+            .map(|(child, _)| child.action.name())
+            .collect();
+        format!("Parallel ({})", running.join(", "))
+    }
+
+    /// The children all start together, so we're done when the slowest one is, or with
+    /// `succeed_on_any`, the quickest
+    fn expected_duration(&self) -> Option<f32> {
+        let durations = self
+            .children
+            .iter()
+            .zip(self.done.iter())
+            .filter(|(_, done)| !**done)
+            .filter_map(|(child, _)| child.action.expected_duration());
+        if self.succeed_on_any {
+            durations.reduce(f32::min)
+        } else {
+            durations.reduce(f32::max)
+        }
+    }
+
+    /// The first running child that has a measure of it
+    fn progress(&self) -> Option<f32> {
+        self.children
+            .iter()
+            .zip(self.done.iter())
+            .filter(|(_, done)| !**done)
+            .find_map(|(child, _)| child.action.progress())
+    }
+
+    fn on_interrupt(&mut self, cause: PreemptCause) {
+        for (child, done) in self.children.iter_mut().zip(self.done.iter()) {
+            if !done {
+                child.interrupt(cause);
+            }
+        }
+    }
+}
+
+/// Fails its child if it takes longer than `limit` seconds
+#[derive(Clone, Debug)]
+pub struct Timeout {
+    child: Child,
+    pub limit: f32,
+    elapsed: f32,
+}
+
+impl Timeout {
+    pub fn new(limit: f32, action: Box<dyn Action>) -> Timeout {
+        Timeout {
+            child: Child::new(action),
+            limit,
+            elapsed: 0.,
+        }
+    }
+}
+
+impl Action for Timeout {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        self.elapsed += dt;
+        if self.elapsed > self.limit {
+            self.child.stop(Err(FailureReason::TimedOut));
+            return ActionResult::Failed(FailureReason::TimedOut);
+        }
+        self.child.step(world, controller, predictions, dt)
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        self.child.action.render()
+    }
+
+    fn interruptible(&self) -> bool {
+        self.child.action.interruptible()
+    }
+
+    fn kickoff(&self) -> bool {
+        self.child.action.kickoff()
+    }
+
+    fn name(&self) -> String {
+        self.child.action.name()
+    }
+
+    fn expected_duration(&self) -> Option<f32> {
+        self.child.action.expected_duration()
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.child.action.progress()
+    }
+
+    fn on_interrupt(&mut self, cause: PreemptCause) {
+        self.child.interrupt(cause);
+    }
+}
+
+/// Builds a fresh action and tries again when it fails, up to `max_attempts` tries in total
+#[derive(Clone, Debug)]
+pub struct Retry {
+    factory: SharedFn<dyn Fn() -> Box<dyn Action>>,
+    child: Child,
+    attempts: u32,
+    pub max_attempts: u32,
+    elapsed: f32,
+}
+
+impl Retry {
+    pub fn new(max_attempts: u32, factory: Box<dyn Fn() -> Box<dyn Action>>) -> Retry {
+        Retry {
+            child: Child::new(factory()),
+            factory: SharedFn::from(factory),
+            attempts: 1,
+            max_attempts,
+            elapsed: 0.,
+        }
+    }
+}
+
+impl Action for Retry {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        self.elapsed += dt;
+        loop {
+            match self.child.step(world, controller.clone(), predictions, dt) {
+                ActionResult::Failed(reason) if self.attempts < self.max_attempts => {
+                    println!("Retrying {}: {reason:?}", self.child.action.name());
+                    self.attempts += 1;
+                    self.child = Child::new((self.factory)());
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        self.child.action.render()
+    }
+
+    fn interruptible(&self) -> bool {
+        self.child.action.interruptible()
+    }
+
+    fn kickoff(&self) -> bool {
+        self.child.action.kickoff()
+    }
+
+    fn name(&self) -> String {
+        format!("{} (try {})", self.child.action.name(), self.attempts)
+    }
+
+    /// The current attempt's, pushed back by however long the failed ones took
+    fn expected_duration(&self) -> Option<f32> {
+        self.child.expected_duration(self.elapsed)
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.child.action.progress()
+    }
+
+    fn on_interrupt(&mut self, cause: PreemptCause) {
+        self.child.interrupt(cause);
+    }
+}
+
+/// Checks something about the world. Either succeeds or fails right away, or with `wait_until`,
+/// leaves the controller alone until the check passes
+#[derive(Clone, Debug)]
+pub struct Condition {
+    name: String,
+    check: SharedFn<dyn Fn(&World) -> bool>,
+    wait: bool,
+}
+
+impl Condition {
+    pub fn new(name: &str, check: Box<dyn Fn(&World) -> bool>) -> Condition {
+        Condition {
+            name: String::from(name),
+            check: SharedFn::from(check),
+            wait: false,
+        }
+    }
+
+    pub fn wait_until(name: &str, check: Box<dyn Fn(&World) -> bool>) -> Condition {
+        Condition {
+            wait: true,
+            ..Condition::new(name, check)
+        }
+    }
+}
+
+impl Action for Condition {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        _predictions: &Vec<PredictionSlice>,
+        _dt: f32,
+    ) -> ActionResult {
+        if (self.check)(world) {
+            ActionResult::Success
+        } else if self.wait {
+            ActionResult::InProgress(ActionTickResult::from(controller))
+        } else {
+            ActionResult::Failed(FailureReason::ConditionFailed)
+        }
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        vec![]
+    }
+
+    fn interruptible(&self) -> bool {
+        true
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+/// Builds its action the first time it runs. For actions that should aim at where things are
+/// when they start, not where they were when the tree was put together
+#[derive(Clone, Debug)]
+pub struct Lazy {
+    build: SharedFn<dyn Fn(&World) -> Box<dyn Action>>,
+    action: Option<Box<dyn Action>>,
+}
+
+impl Lazy {
+    pub fn new(build: Box<dyn Fn(&World) -> Box<dyn Action>>) -> Lazy {
+        Lazy {
+            build: SharedFn::from(build),
+            action: None,
+        }
+    }
+}
+
+impl Action for Lazy {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        if self.action.is_none() {
+            self.on_start(world);
+        }
+        match self.action.as_mut() {
+            Some(action) => action.step(world, controller, predictions, dt),
+            None => ActionResult::Failed(FailureReason::ConditionFailed),
+        }
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        match self.action.as_ref() {
+            Some(action) => action.render(),
+            None => vec![],
+        }
+    }
+
+    fn interruptible(&self) -> bool {
+        match self.action.as_ref() {
+            Some(action) => action.interruptible(),
+            None => true,
+        }
+    }
+
+    fn kickoff(&self) -> bool {
+        match self.action.as_ref() {
+            Some(action) => action.kickoff(),
+            None => false,
+        }
+    }
+
+    fn name(&self) -> String {
+        match self.action.as_ref() {
+            Some(action) => action.name(),
+            None => String::from("Lazy"),
+        }
+    }
+
+    fn expected_duration(&self) -> Option<f32> {
+        self.action.as_ref().and_then(|action| action.expected_duration())
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.action.as_ref().and_then(|action| action.progress())
+    }
+
+    fn on_start(&mut self, world: &World) {
+        let mut action = (self.build)(world);
+        action.on_start(world);
+        self.action = Some(action);
+    }
+
+    fn on_interrupt(&mut self, cause: PreemptCause) {
+        if let Some(action) = self.action.as_mut() {
+            action.on_interrupt(cause);
+        }
+    }
+
+    fn on_finish(&mut self, result: Result<(), FailureReason>) {
+        if let Some(action) = self.action.as_mut() {
+            action.on_finish(result);
+        }
+    }
+}
+
+/// Runs a base action with layers on top of it. Each layer only gets a say on its own
+/// `channels`, and higher priority layers win when they own the same channel. The base decides
+/// when the whole thing is done, layers that finish early just drop out
+#[derive(Clone, Debug)]
+pub struct Blend {
+    base: Child,
+    /// Kept sorted by priority, lowest first
+    layers: Vec<(i32, Child)>,
+}
+
+impl Blend {
+    pub fn new(base: Box<dyn Action>) -> Blend {
+        Blend {
+            base: Child::new(base),
+            layers: vec![],
+        }
+    }
+
+    pub fn layer(mut self, priority: i32, action: Box<dyn Action>) -> Blend {
+        self.layers.push((priority, Child::new(action)));
+        self.layers.sort_by_key(|(priority, _)| *priority);
+        self
+    }
+}
+
+impl Action for Blend {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        let mut action_result = match self.base.step(world, controller, predictions, dt) {
+            ActionResult::InProgress(res) => res,
+            result => {
+                for (_, layer) in self.layers.iter_mut() {
+                    layer.stop(Ok(()));
+                }
+                return result;
+            }
+        };
+        // layers see what's been decided so far, so a boost layer can tell whether the base
+        // wants to boost
+        self.layers.retain_mut(|(_, layer)| {
+            let controller = action_result.controller.clone();
+            match layer.step(world, controller, predictions, dt) {
+                ActionResult::InProgress(mut res) => {
+                    overlay(
+                        &mut action_result.controller,
+                        &res.controller,
+                        &layer.action.channels(),
+                    );
+                    action_result.render.append(&mut res.render);
+                    true
+                }
+                _ => false,
+            }
+        });
+        ActionResult::InProgress(action_result)
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        let mut renders = self.base.action.render();
+        for (_, layer) in self.layers.iter() {
+            renders.append(&mut layer.action.render());
+        }
+        renders
+    }
+
+    fn interruptible(&self) -> bool {
+        self.base.action.interruptible()
+    }
+
+    fn kickoff(&self) -> bool {
+        self.base.action.kickoff()
+    }
+
+    fn name(&self) -> String {
+        self.base.action.name()
+    }
+
+    fn expected_duration(&self) -> Option<f32> {
+        self.base.action.expected_duration()
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.base.action.progress()
+    }
+
+    fn on_interrupt(&mut self, cause: PreemptCause) {
+        self.base.interrupt(cause);
+        for (_, layer) in self.layers.iter_mut() {
+            layer.interrupt(cause);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use rlbot_lib::rlbot::{ControllerState, PredictionSlice, RenderMessage};
+
+use crate::utils::{channels::channels::Channel, world::world::World, ActionTickResult};
+
+use super::action::{Action, ActionResult, FailureReason};
+
+/// Boost management, meant to run as a Blend layer on top of a driving action. Lets whatever is
+/// underneath boost as it likes, but holds on to a reserve and doesn't waste boost once we're
+/// supersonic
+#[derive(Clone, Debug)]
+pub struct BoostLayer {
+    car_id: usize,
+    /// Never boost below this much
+    pub reserve: i32,
+}
+
+impl BoostLayer {
+    pub fn new(car_id: usize, reserve: i32) -> BoostLayer {
+        BoostLayer { car_id, reserve }
+    }
+}
+
+impl Action for BoostLayer {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        _predictions: &Vec<PredictionSlice>,
+        _dt: f32,
+    ) -> ActionResult {
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return ActionResult::Failed(FailureReason::CarMissing),
+        };
+        let mut controller = controller.clone();
+        if car.boost <= self.reserve || (car.is_supersonic && car.has_wheel_contact) {
+            controller.boost = false;
+        }
+        ActionResult::InProgress(ActionTickResult::from(controller))
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        vec![]
+    }
+
+    fn interruptible(&self) -> bool {
+        true
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        String::from("BoostLayer")
+    }
+
+    fn channels(&self) -> Vec<Channel> {
+        vec![Channel::Boost]
+    }
+}
@@ -0,0 +1,274 @@
+use rlbot_lib::rlbot::{ControllerState, PredictionSlice, RenderMessage, Vector3};
+
+use crate::utils::{
+    intercept::estimate_time,
+    math::math::{abs_clamp, clamp, forward_vec, to_local, Vec3},
+    render::render::{cross, GREEN},
+    world::world::{Ball, Car, World},
+    ActionTickResult,
+};
+
+use super::{
+    action::{Action, ActionResult, FailureReason},
+    goto_action::GotoAction,
+};
+
+/// How far above the car's centre the ball's centre can be and still be sitting on the roof
+const MIN_ABOVE: f32 = 70.;
+const MAX_ABOVE: f32 = 250.;
+/// and how far off the middle of the roof, sideways and front to back
+const ON_CAR_RADIUS: f32 = 110.;
+/// Where on the roof to keep the ball, a bit forwards of the middle
+const HOLD_OFFSET: f32 = 15.;
+/// Gains for holding the ball there. Speed is in uu/s per uu of offset, or per uu/s of the
+/// ball rolling relative to the car
+const SPEED_KP: f32 = 6.;
+const SPEED_KD: f32 = 1.;
+/// and steering is in stick per uu of sideways offset, or per uu/s sideways
+const STEER_KP: f32 = 0.02;
+const STEER_KD: f32 = 0.005;
+/// How much speed error is full throttle, and how much more is worth boosting for
+const THROTTLE_RANGE: f32 = 300.;
+const BOOST_ERROR: f32 = 500.;
+/// Most steering we'll spend turning towards the target, the rest is for keeping the ball on
+const MAX_TARGET_STEER: f32 = 0.3;
+/// Balls lower than this and not bouncing much can be caught
+const CATCH_HEIGHT: f32 = 200.;
+const CATCH_MAX_VZ: f32 = 300.;
+/// How late the ball can be on the car before we stop waiting for it
+const CATCH_SLACK: f32 = 0.5;
+/// Plan the catch again once the prediction puts the ball this far from where we planned it
+const REPLAN_DIST: f32 = 150.;
+/// Opponents this close to the ball and driving at it are coming to take it
+const CHALLENGE_DIST: f32 = 1000.;
+const CHALLENGE_SPEED: f32 = 500.;
+/// Close enough to the target with the ball that we're done
+const ARRIVE_DIST: f32 = 300.;
+
+/// Catches the ball on the roof and carries it to `target`, leaning the car under it to keep it
+/// from rolling off
+#[derive(Clone, Debug)]
+pub struct DribbleAction {
+    car_id: usize,
+    /// Where we're taking the ball
+    pub target: Vector3,
+    /// The ball where we're catching it, and secondsElapsed when it's there
+    catch: Option<(Ball, f32)>,
+    /// When the ball had last been touched as of planning the catch
+    catch_touch: Option<f32>,
+    goto: Option<GotoAction>,
+    /// Passed on to the GotoAction we catch with
+    pub goto_lerp_t: f32,
+    /// Whether the ball has made it onto the car
+    carrying: bool,
+    /// distance left from the ball to the target as of the last step
+    remaining_dist: Option<f32>,
+}
+
+impl DribbleAction {
+    pub fn new(car_id: usize, target: Vector3) -> DribbleAction {
+        DribbleAction {
+            car_id,
+            target,
+            catch: None,
+            catch_touch: None,
+            goto: None,
+            goto_lerp_t: GotoAction::DEFAULT_LERP_T,
+            carrying: false,
+            remaining_dist: None,
+        }
+    }
+
+    pub fn with_lerp_t(mut self, lerp_t: f32) -> DribbleAction {
+        self.goto_lerp_t = lerp_t;
+        self
+    }
+
+    /// Whether the ball is sitting on the car's roof
+    pub fn on_car(car: &Car, ball: &Ball) -> bool {
+        let offset = to_local(&ball.location.sub(&car.location), &car.rotation);
+        offset.z > MIN_ABOVE
+            && offset.z < MAX_ABOVE
+            && offset.x.abs() < ON_CAR_RADIUS
+            && offset.y.abs() < ON_CAR_RADIUS
+    }
+
+    /// The first ball low and slow enough to get under that we can drive to in time, and when
+    /// it's there
+    pub fn find_catch(
+        car: &Car,
+        game_time: f32,
+        predictions: &Vec<PredictionSlice>,
+    ) -> Option<(Ball, f32)> {
+        predictions.iter().find_map(|slice| {
+            let ball = slice.physics.as_ref().and_then(|p| Ball::from_physics(p))?;
+            let catchable = ball.location.z < CATCH_HEIGHT
+                && ball.velocity.z.abs() < CATCH_MAX_VZ
+                && estimate_time(car, ball.location.clone()) < slice.gameSeconds - game_time;
+            if catchable {
+                Some((ball, slice.gameSeconds))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Whether the catch we planned still holds up: nobody's touched the ball since, and the
+    /// prediction still has it where we're waiting
+    fn catch_still_good(&self, ball: &Ball, predictions: &Vec<PredictionSlice>) -> bool {
+        let (catch_ball, catch_time) = match self.catch.as_ref() {
+            Some(catch) => catch,
+            None => return false,
+        };
+        if ball.latest_touch.as_ref().map(|touch| touch.game_seconds) != self.catch_touch {
+            return false;
+        }
+        // no slice for it means it's already due, so there's nothing newer to go on
+        predictions
+            .iter()
+            .find(|slice| slice.gameSeconds >= *catch_time)
+            .and_then(|slice| slice.physics.as_ref().and_then(|p| Ball::from_physics(p)))
+            .map_or(true, |predicted| {
+                predicted.location.dist(&catch_ball.location) < REPLAN_DIST
+            })
+    }
+
+    /// Whether an opponent is close to the ball and heading for it
+    pub fn challenged(world: &World, car: &Car) -> bool {
+        let ball = &world.ball.location;
+        world.opponents(car.team).any(|opp| {
+            let to_ball = ball.sub(&opp.location);
+            to_ball.norm() < CHALLENGE_DIST
+                && opp.velocity.dot(&to_ball.normalize()) > CHALLENGE_SPEED
+        })
+    }
+
+    /// Whether the ball is already on the car, or close and slow enough to catch, with nobody
+    /// around to take it off us
+    pub fn worth_it(world: &World, car: &Car) -> bool {
+        let ball = &world.ball;
+        let catchable = ball.location.z < CATCH_HEIGHT
+            && ball.velocity.norm() < 1000.
+            && car.location.ground_dist(&ball.location) < 1500.;
+        (DribbleAction::on_car(car, ball) || catchable) && !DribbleAction::challenged(world, car)
+    }
+
+    /// Keep the ball on the roof while turning towards the target
+    fn carry(&self, car: &Car, ball: &Ball, controller: ControllerState) -> ControllerState {
+        let offset = to_local(&ball.location.sub(&car.location), &car.rotation);
+        let rolling = to_local(&ball.velocity.sub(&car.velocity), &car.rotation);
+        let mut controller = controller;
+
+        // the ball getting ahead of us, or rolling forwards, means speeding up to get back
+        // under it
+        let speed_error = SPEED_KP * (offset.x - HOLD_OFFSET) + SPEED_KD * rolling.x;
+        controller.throttle = clamp(speed_error / THROTTLE_RANGE, -1., 1.);
+        controller.boost = speed_error > BOOST_ERROR;
+
+        // y is to the left, and steering left is negative
+        let hold = -(STEER_KP * offset.y + STEER_KD * rolling.y);
+        let to_target = to_local(&self.target.sub(&car.location), &car.rotation);
+        let turn = -to_target.y.atan2(to_target.x);
+        controller.steer = clamp(hold + abs_clamp(turn, MAX_TARGET_STEER), -1., 1.);
+        controller.handbrake = false;
+        controller
+    }
+}
+
+impl Action for DribbleAction {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        let game_time = world.game.seconds_elapsed;
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return ActionResult::Failed(FailureReason::CarMissing),
+        };
+        let ball = &world.ball;
+
+        if DribbleAction::challenged(world, car) {
+            return ActionResult::Failed(FailureReason::Challenged);
+        }
+
+        if self.carrying || DribbleAction::on_car(car, ball) {
+            if !DribbleAction::on_car(car, ball) {
+                return ActionResult::Failed(FailureReason::BallLost);
+            }
+            self.carrying = true;
+            let remaining_dist = ball.location.ground_dist(&self.target);
+            self.remaining_dist = Some(remaining_dist);
+            if remaining_dist < ARRIVE_DIST {
+                return ActionResult::Success;
+            }
+            let controller = self.carry(car, ball, controller);
+            return ActionResult::InProgress(ActionTickResult::from(controller));
+        }
+
+        // get under the ball first, planning again whenever it's been hit or bounced off
+        // somewhere else
+        if !self.catch_still_good(ball, predictions) {
+            self.catch = DribbleAction::find_catch(car, game_time, predictions);
+            self.catch_touch = ball.latest_touch.as_ref().map(|touch| touch.game_seconds);
+        }
+        let (catch_ball, catch_time) = match self.catch.as_ref() {
+            Some(catch) => catch,
+            None => return ActionResult::Failed(FailureReason::TargetUnreachable),
+        };
+        if game_time > catch_time + CATCH_SLACK {
+            return ActionResult::Failed(FailureReason::TimedOut);
+        }
+
+        // come in behind the ball, going the way it's rolling or else towards the target, so it
+        // rolls up onto the roof instead of getting knocked away
+        let catch_location = catch_ball.location.ground();
+        let direction = if catch_ball.velocity.ground().norm() > 100. {
+            catch_ball.velocity.ground()
+        } else {
+            self.target.ground().sub(&catch_location)
+        };
+        let car_id = self.car_id;
+        let goto = self
+            .goto
+            .get_or_insert_with(|| GotoAction::new(catch_location.clone(), None, car_id));
+        goto.target = catch_location;
+        goto.target_direction = Some(direction);
+        goto.lerp_t = self.goto_lerp_t;
+        goto.arrival_time = *catch_time;
+        match goto.step(world, controller.clone(), predictions, dt) {
+            ActionResult::InProgress(res) => ActionResult::InProgress(res),
+            // there already, wait for the ball to get here
+            ActionResult::Success => ActionResult::InProgress(ActionTickResult::from(controller)),
+            failed => failed,
+        }
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        cross(&self.target, 100., GREEN)
+    }
+
+    /// Not once we've got the ball, dropping it for something else is a waste
+    fn interruptible(&self) -> bool {
+        !self.carrying
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    /// Only while carrying, the catch can look like it's going nowhere
+    fn progress(&self) -> Option<f32> {
+        self.remaining_dist
+    }
+
+    fn name(&self) -> String {
+        if self.carrying {
+            String::from("DribbleAction (carrying)")
+        } else {
+            String::from("DribbleAction (catching)")
+        }
+    }
+}
@@ -1,42 +1,100 @@
-use rlbot_lib::rlbot::{ControllerState, GameTickPacket, PredictionSlice, RenderMessage, Vector3};
+use std::f32::consts::PI;
+
+use rlbot_lib::rlbot::{ControllerState, PredictionSlice, RenderMessage, Vector3};
 
 use crate::{
     utils::{
-        arena::Arena,
-        math::math::{abs_clamp, forward_vec, up_vec, Vec3},
+        arena::{Arena, Surface},
+        math::math::{abs_clamp, forward_vec, left_vec, up_vec, wrap_angle, Vec3},
         render::render::{cross, RED},
+        world::world::World,
         ActionTickResult,
     },
-    DEFAULT_CAR_ID,
 };
 
-use super::action::{Action, ActionResult};
+use super::{
+    action::{Action, ActionResult, FailureReason},
+    wavedash_action::WavedashAction,
+};
+
+/// Never back up further than this, it's slow and we can't boost
+const MAX_REVERSE_DIST: f32 = 1500.;
+/// Rough average speeds from a standstill, for comparing going forwards and backwards
+const FORWARD_SPEED: f32 = 1400.;
+const REVERSE_SPEED: f32 = 1000.;
+/// Roughly how fast we can turn on the ground, in radians per second
+const TURN_RATE: f32 = 2.5;
+/// Deceleration from braking before we can change direction
+const BRAKE_ACCEL: f32 = 3500.;
+/// Stick with whichever way we're going unless the other is at least this many seconds faster,
+/// so we don't flip back and forth
+const REVERSE_HYSTERESIS: f32 = 0.2;
+/// Only count distance as progress once we're pointed within this many radians of the target.
+/// Turning around takes us further away for a while first
+const FACING_ANGLE: f32 = 0.5;
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct DriveAction {
     // track the progress of this action, b/c this is a timed uninterruptible action
+    car_id: usize,
     pub target_pos: Vector3,
     pub target_speed: f32,
     pub drive_on_walls: bool,
     pub slow_on_approach: bool,
     starting_dist: Option<f32>,
     powerslide_counter: i32,
+    expected_duration: Option<f32>,
+    /// distance left to the target as of the last step, once we're pointed at it
+    remaining_dist: Option<f32>,
+    /// Back up to targets behind us when that's quicker than turning around
+    pub allow_reverse: bool,
+    reversing: bool,
+    /// Wavedash for speed on straight drives without boost. Off unless the caller knows it's a
+    /// long run, and turns itself off when they aren't speeding us up
+    pub allow_wavedash: bool,
+    wavedash: Option<WavedashAction>,
 }
 
 impl DriveAction {
     pub fn new(
+        car_id: usize,
         target_pos: Vector3,
         target_speed: f32,
         drive_on_walls: bool,
         slow_on_approach: bool,
     ) -> DriveAction {
         DriveAction {
+            car_id,
             target_pos,
             target_speed,
             drive_on_walls,
             slow_on_approach,
             starting_dist: None,
             powerslide_counter: 0,
+            expected_duration: None,
+            remaining_dist: None,
+            allow_reverse: true,
+            reversing: false,
+            allow_wavedash: false,
+            wavedash: None,
+        }
+    }
+
+    /// Whether backing up to a target `angle` radians off our nose and `dist` away beats turning
+    /// around, with `forward_vel` being how fast we're already going forwards
+    pub fn should_reverse(angle: f32, dist: f32, forward_vel: f32, reversing: bool) -> bool {
+        if dist > MAX_REVERSE_DIST {
+            return false;
+        }
+        let angle = wrap_angle(angle).abs();
+        let forward_time =
+            angle / TURN_RATE + dist / FORWARD_SPEED + (-forward_vel).max(0.) / BRAKE_ACCEL;
+        let reverse_time =
+            (PI - angle) / TURN_RATE + dist / REVERSE_SPEED + forward_vel.max(0.) / BRAKE_ACCEL;
+        if reversing {
+            reverse_time < forward_time + REVERSE_HYSTERESIS
+        } else {
+            reverse_time + REVERSE_HYSTERESIS < forward_time
         }
     }
 }
@@ -45,32 +103,18 @@ impl DriveAction {
 impl Action for DriveAction {
     fn step(
         &mut self,
-        tick_packet: GameTickPacket,
+        world: &World,
         controller: ControllerState,
-        _predictions: &Vec<PredictionSlice>,
-        _dt: f32,
+        predictions: &Vec<PredictionSlice>,
+        dt: f32,
     ) -> ActionResult {
-        let car = tick_packet
-            .clone()
-            .players
-            .unwrap()
-            .get(DEFAULT_CAR_ID)
-            .unwrap()
-            .physics
-            .clone()
-            .unwrap();
-        let car_location = car.location.clone().unwrap();
-        let rotation = car.rotation.clone().unwrap();
-        let velocity = car.velocity.clone().unwrap();
-
-        if self.starting_dist.is_none() {
-            self.starting_dist = Some(car_location.ground_dist(&self.target_pos));
-        }
-
-        // slow down as we start approaching the target
-        if self.slow_on_approach && car_location.ground_dist(&self.target_pos) < 1000. {
-            self.target_speed = self.target_speed.min(500.);
-        }
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return ActionResult::Failed(FailureReason::CarMissing),
+        };
+        let car_location = car.location.clone();
+        let rotation = car.rotation.clone();
+        let velocity = car.velocity.clone();
 
         // don't try driving outside the arena
         let mut target = Arena::clamp(&self.target_pos, 100.);
@@ -80,92 +124,169 @@ impl Action for DriveAction {
             target = Arena::clamp(&target, 200.);
             target.x = abs_clamp(target.x, 700.);
         }
-        if !self.drive_on_walls {
-            let seam_radius: f32 = if car_location.y.abs() > Arena::SIZE.y - 100. {
-                100.
-            } else {
-                200.
-            };
 
-            if car_location.z > seam_radius {
-                // target the point on the ground below the car
-                target = target.ground();
+        let mut target_surface = Arena::surface(&target);
+        if target_surface != Surface::Floor && !(self.drive_on_walls && Arena::drivable(&target)) {
+            // stay on the floor and go under it
+            target = target.ground();
+            target_surface = Surface::Floor;
+        }
+        // on the floor the target's height doesn't matter, up the walls it does
+        let on_floor = target_surface == Surface::Floor;
+        let dist_to_target = |pos: &Vector3| {
+            if on_floor {
+                pos.ground_dist(&self.target_pos)
+            } else {
+                pos.dist(&target)
             }
+        };
+
+        if self.starting_dist.is_none() {
+            let dist = dist_to_target(&car_location);
+            self.starting_dist = Some(dist);
+            // time to cover the distance at the speed we're going for, plus some to turn around
+            self.expected_duration = Some(dist / self.target_speed.clamp(500., 2300.) + 1.);
         }
 
-        // NOTE: This is a place we'd normally want some type of logic for driving backwards
-        let bot_to_target_angle =
-            (self.target_pos.y - car_location.y).atan2(target.x - car_location.x);
-        let bot_front_to_target_angle = bot_to_target_angle - rotation.yaw;
+        // slow down as we start approaching the target
+        if self.slow_on_approach && dist_to_target(&car_location) < 1000. {
+            self.target_speed = self.target_speed.min(500.);
+        }
 
-        let mut controller = controller.clone();
+        // steer in the plane of whatever we're driving on, with the target folded out into it if
+        // it's on another surface, so we cross the seam heading the right way
+        let car_surface = Surface::from_up(&up_vec(&rotation));
+        let local_target = target_surface.unfold(&target, car_surface).sub(&car_location);
+        let local_x = local_target.dot(&forward_vec(&rotation));
+        let local_y = -local_target.dot(&left_vec(&rotation));
+        let bot_front_to_target_angle = local_y.atan2(local_x);
+        let surface_dist = (local_x * local_x + local_y * local_y).sqrt();
 
-        controller.steer = abs_clamp(2.5 * bot_front_to_target_angle, 1.);
-        controller.throttle = 1.;
+        let forward_vec = forward_vec(&rotation);
+        let forward_vel = velocity.dot(&forward_vec);
 
-        // powerslide code
-        // powerslide if facing away from ball and tap it, so we don't spin out
-        if bot_front_to_target_angle.abs() > 0.9 {
-            if self.powerslide_counter > 0 {
-                self.powerslide_counter -= 1;
-            } else {
-                controller.handbrake = true;
-                self.powerslide_counter = 100;
-            }
+        self.reversing = self.allow_reverse
+            && DriveAction::should_reverse(
+                bot_front_to_target_angle,
+                surface_dist,
+                forward_vel,
+                self.reversing,
+            );
+        let heading_angle = if self.reversing {
+            wrap_angle(bot_front_to_target_angle - PI)
         } else {
-            controller.handbrake = false;
-        }
+            bot_front_to_target_angle
+        };
+        self.remaining_dist = if heading_angle.abs() < FACING_ANGLE {
+            Some(dist_to_target(&car_location))
+        } else {
+            None
+        };
 
-        // Speed controller
+        let mut controller = controller.clone();
 
-        let forward_vec = forward_vec(&rotation);
-        let forward_vel = velocity.dot(&forward_vec);
+        if self.wavedash.is_none()
+            && self.allow_wavedash
+            && !self.reversing
+            && bot_front_to_target_angle.abs() < 0.1
+            && WavedashAction::worth_it(car, surface_dist, self.target_speed)
+        {
+            self.wavedash = Some(WavedashAction::new(self.car_id));
+        }
+        // once we've hopped we have to see it through to the landing
+        if let Some(wavedash) = self.wavedash.as_mut() {
+            match wavedash.step(world, controller.clone(), predictions, dt) {
+                ActionResult::InProgress(res) => return ActionResult::InProgress(res),
+                _ => {
+                    if wavedash.speed_gain().map_or(true, |gain| gain <= 0.) {
+                        self.allow_wavedash = false;
+                    }
+                    self.wavedash = None;
+                }
+            }
+        }
 
-        // println!("{forward_vel:?}");
-        // # speed controller
-        if forward_vel < self.target_speed {
-            // println!("moving slower than target speed");
-            controller.throttle = 1.0;
-            if self.target_speed > 1400.0
-                && forward_vel < 2250.0
-                && self.target_speed - forward_vel > 50.0
-            {
-                controller.boost = true;
+        if self.reversing {
+            // steering is flipped going backwards, so steer away from where the back should go
+            let bot_back_to_target_angle = wrap_angle(bot_front_to_target_angle - PI);
+            controller.steer = -abs_clamp(2.5 * bot_back_to_target_angle, 1.);
+            controller.handbrake = false;
+            controller.boost = false;
+            // same speed controller as going forwards, with the pedals swapped
+            let backward_vel = -forward_vel;
+            controller.throttle = if backward_vel - self.target_speed > 400. {
+                1.
+            } else if backward_vel - self.target_speed > 100. {
+                0.
             } else {
-                controller.boost = false;
-            }
+                -1.
+            };
         } else {
-            // println!("moving faster than target speed");
-            if (forward_vel - self.target_speed) > 400.0 {
-                // tap break if we're moving too much faster than the target speed
-                controller.throttle = -1.0;
-            } else if (forward_vel - self.target_speed) > 100.0 {
-                // release the throttle if we're only slightly above target speed
-                if up_vec(&rotation).z > 0.85 {
-                    controller.throttle = 0.0;
+            controller.steer = abs_clamp(2.5 * bot_front_to_target_angle, 1.);
+            controller.throttle = 1.;
+
+            // powerslide code
+            // powerslide if facing away from ball and tap it, so we don't spin out. Not on the
+            // walls though, we'd slide right off
+            if bot_front_to_target_angle.abs() > 0.9 && car_surface == Surface::Floor {
+                if self.powerslide_counter > 0 {
+                    self.powerslide_counter -= 1;
                 } else {
-                    // don't release if we're on the wall though
-                    controller.throttle = 0.01;
+                    controller.handbrake = true;
+                    self.powerslide_counter = 100;
                 }
-                controller.boost = false;
+            } else {
+                controller.handbrake = false;
             }
-        }
 
-        // only boost when we're facing the target
-        if bot_front_to_target_angle.abs() > 0.3 {
-            controller.boost = false;
+            // Speed controller
+
+            // println!("{forward_vel:?}");
+            // # speed controller
+            if forward_vel < self.target_speed {
+                // println!("moving slower than target speed");
+                controller.throttle = 1.0;
+                if self.target_speed > 1400.0
+                    && forward_vel < 2250.0
+                    && self.target_speed - forward_vel > 50.0
+                {
+                    controller.boost = true;
+                } else {
+                    controller.boost = false;
+                }
+            } else {
+                // println!("moving faster than target speed");
+                if (forward_vel - self.target_speed) > 400.0 {
+                    // tap break if we're moving too much faster than the target speed
+                    controller.throttle = -1.0;
+                } else if (forward_vel - self.target_speed) > 100.0 {
+                    // release the throttle if we're only slightly above target speed
+                    if up_vec(&rotation).z > 0.85 {
+                        controller.throttle = 0.0;
+                    } else {
+                        // don't release if we're on the wall though
+                        controller.throttle = 0.01;
+                    }
+                    controller.boost = false;
+                }
+            }
+
+            // only boost when we're facing the target
+            if bot_front_to_target_angle.abs() > 0.3 {
+                controller.boost = false;
+            }
         }
 
-        if self.target_pos.ground_dist(&car_location) < 150. {
+        if dist_to_target(&car_location) < 150. {
             println!("Drive Success");
             return ActionResult::Success;
         }
 
         // fail if we're significantly further from the ball when we were when we started
         if let Some(d) = self.starting_dist {
-            if self.target_pos.ground_dist(&car_location) > d + 400. {
+            if dist_to_target(&car_location) > d + 400. {
                 println!("failed drive");
-                return ActionResult::Failed;
+                return ActionResult::Failed(FailureReason::TargetUnreachable);
             }
         }
 
@@ -180,12 +301,22 @@ impl Action for DriveAction {
         true
     }
 
+    fn expected_duration(&self) -> Option<f32> {
+        self.expected_duration
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.remaining_dist
+    }
+
     fn kickoff(&self) -> bool {
         false
     }
 
     fn name(&self) -> String {
-        if self.target_speed <= 501. {
+        if self.reversing {
+            String::from("DriveAction (reverse)")
+        } else if self.target_speed <= 501. {
             String::from("DriveAction (approach)")
         } else {
             String::from("DriveAction")
@@ -3,14 +3,15 @@ use rlbot_lib::rlbot::{PredictionSlice, Vector3};
 use crate::utils::{
     intercept::turn_radius,
     math::math::{clamp, forward_vec, Vec3},
+    world::world::World,
 };
 
 use super::{
-    action::{Action, ActionResult},
+    action::{Action, ActionResult, FailureReason},
     drive_action::DriveAction,
 };
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct GotoAction {
     pub drive: DriveAction,
     pub target: Vector3,
@@ -18,19 +19,26 @@ pub struct GotoAction {
     car_id: usize,
     pub arrival_time: f32,
     pub additional_shift: f32,
-    lerp_t: f32,
+    /// How far to shift the target to arrive from `target_direction`, as a fraction of the
+    /// distance to the target
+    pub lerp_t: f32,
+    /// secondsElapsed on our first step
+    start_time: Option<f32>,
 }
 
 impl GotoAction {
+    pub const DEFAULT_LERP_T: f32 = 0.56;
+
     pub fn new(target: Vector3, target_direction: Option<Vector3>, car_id: usize) -> GotoAction {
         GotoAction {
-            drive: DriveAction::new(target.clone(), 0., false, true),
+            drive: DriveAction::new(car_id, target.clone(), 0., false, true),
             target,
             target_direction,
             car_id,
             arrival_time: f32::MAX,
             additional_shift: 0.,
-            lerp_t: 0.56,
+            lerp_t: GotoAction::DEFAULT_LERP_T,
+            start_time: None,
         }
     }
 }
@@ -38,23 +46,19 @@ impl GotoAction {
 impl Action for GotoAction {
     fn step(
         &mut self,
-        tick_packet: rlbot_lib::rlbot::GameTickPacket,
+        world: &World,
         controller: rlbot_lib::rlbot::ControllerState,
         predictions: &Vec<PredictionSlice>,
         dt: f32,
     ) -> ActionResult {
-        let car = tick_packet
-            .clone()
-            .players
-            .unwrap()
-            .get(self.car_id)
-            .unwrap()
-            .physics
-            .clone()
-            .unwrap();
-        let car_location = car.location.clone().unwrap();
-        let rotation = car.rotation.clone().unwrap();
-        let velocity = car.velocity.clone().unwrap();
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return ActionResult::Failed(FailureReason::CarMissing),
+        };
+        self.start_time.get_or_insert(world.game.seconds_elapsed);
+        let car_location = car.location.clone();
+        let rotation = car.rotation.clone();
+        let velocity = car.velocity.clone();
         let car_forward = forward_vec(&rotation);
 
         let mut shifted_target = self.target.clone();
@@ -85,10 +89,11 @@ impl Action for GotoAction {
         }
 
         self.drive.target_pos = shifted_target.clone();
+        // backing up would have us arrive facing the wrong way
+        self.drive.allow_reverse = self.target_direction.is_none();
         let dist_to_target = car_location.ground_dist(&shifted_target);
 
-        let time_left =
-            (shifted_arrival_time - tick_packet.gameInfo.clone().unwrap().secondsElapsed).max(1e-6);
+        let time_left = (shifted_arrival_time - world.game.seconds_elapsed).max(1e-6);
         let mut target_speed = clamp(dist_to_target / time_left, 0., 2300.);
         if target_speed < 800.
             && dist_to_target > 1000.
@@ -98,7 +103,7 @@ impl Action for GotoAction {
         }
         self.drive.target_speed = target_speed;
 
-        self.drive.step(tick_packet, controller, predictions, dt)
+        self.drive.step(world, controller, predictions, dt)
     }
 
     fn render(&self) -> Vec<rlbot_lib::rlbot::RenderMessage> {
@@ -109,6 +114,18 @@ impl Action for GotoAction {
         true
     }
 
+    /// We should be there by `arrival_time`, when there is one
+    fn expected_duration(&self) -> Option<f32> {
+        match self.start_time {
+            Some(start) if self.arrival_time < f32::MAX => Some(self.arrival_time - start + 0.5),
+            _ => self.drive.expected_duration(),
+        }
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.drive.progress()
+    }
+
     fn kickoff(&self) -> bool {
         false
     }
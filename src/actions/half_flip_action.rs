@@ -0,0 +1,154 @@
+use rlbot_lib::rlbot::{ControllerState, PredictionSlice, RenderMessage, Vector3};
+
+use crate::utils::{
+    math::math::{forward_vec, left_vec, Vec3},
+    world::world::{Car, World},
+    ActionTickResult,
+};
+
+use super::{
+    action::{Action, ActionResult, FailureReason},
+    drive_action::DriveAction,
+    jump_action::JumpAction,
+    reorient_action::ReorientAction,
+};
+
+/// How long to hold the first jump, just enough to get the wheels off the ground
+const FLIP_JUMP_TIME: f32 = 0.1;
+/// How long to let the backflip go before cancelling it, about when the nose is pointing up
+const CANCEL_DELAY: f32 = 0.1;
+/// How long to hold the stick forward to stop the flip
+const CANCEL_TIME: f32 = 0.25;
+/// Should be back on the ground well before this
+const MAX_TIME: f32 = 2.;
+/// Only worth it for targets at least this far off our nose, in radians
+const MIN_ANGLE: f32 = 2.5;
+
+/// Turns the car around quickly: a short jump, a backflip that gets cancelled halfway, then an
+/// air roll so we land on our wheels facing the other way
+#[derive(Clone, Debug)]
+pub struct HalfFlipAction {
+    car_id: usize,
+    jump: JumpAction,
+    jump_finished: bool,
+    /// ticks since the jump finished
+    counter: i32,
+    timer: f32,
+    /// Which way we want to be facing when we land, straight back from where we started
+    facing: Option<Vector3>,
+}
+
+impl HalfFlipAction {
+    pub fn new(car_id: usize) -> HalfFlipAction {
+        HalfFlipAction {
+            car_id,
+            jump: JumpAction::new(FLIP_JUMP_TIME),
+            jump_finished: false,
+            counter: 0,
+            timer: 0.,
+            facing: None,
+        }
+    }
+
+    /// Whether flipping around beats turning or backing up to get to `target`
+    pub fn worth_it(car: &Car, target: &Vector3) -> bool {
+        let to_target = target.sub(&car.location);
+        let forward = forward_vec(&car.rotation);
+        let local_x = to_target.dot(&forward);
+        let local_y = -to_target.dot(&left_vec(&car.rotation));
+        let angle = local_y.atan2(local_x);
+        let forward_vel = car.velocity.dot(&forward);
+        car.has_wheel_contact
+            && angle.abs() > MIN_ANGLE
+            // it's slow to stop first when we're already going forwards
+            && forward_vel < 500.
+            && !DriveAction::should_reverse(angle, to_target.ground().norm(), forward_vel, false)
+    }
+}
+
+impl Action for HalfFlipAction {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return ActionResult::Failed(FailureReason::CarMissing),
+        };
+        let facing = self
+            .facing
+            .get_or_insert_with(|| forward_vec(&car.rotation).ground().normalize().scale(-1.))
+            .clone();
+
+        self.timer += dt;
+        if self.timer > MAX_TIME {
+            return ActionResult::Failed(FailureReason::TimedOut);
+        }
+
+        let mut controller = controller;
+        if !self.jump_finished {
+            match self.jump.step(world, controller.clone(), predictions, dt) {
+                ActionResult::InProgress(res) => return ActionResult::InProgress(res),
+                _ => self.jump_finished = true,
+            }
+        }
+
+        let since_flip = self.counter as f32 * dt;
+        self.counter += 1;
+        controller.throttle = 1.;
+        match self.counter {
+            // stick back, then jump again to flip backwards
+            1 => controller.pitch = 1.,
+            2 => {
+                controller.pitch = 1.;
+                controller.jump = true;
+            }
+            _ if since_flip < CANCEL_DELAY => {}
+            _ => {
+                if car.has_wheel_contact {
+                    return ActionResult::Success;
+                }
+                let mut reorient = ReorientAction::from_uf(Vector3::up(), facing, self.car_id);
+                match reorient.step(world, controller.clone(), predictions, dt) {
+                    ActionResult::InProgress(res) => {
+                        controller.roll = res.controller.roll;
+                        controller.pitch = res.controller.pitch;
+                        controller.yaw = res.controller.yaw;
+                    }
+                    // upright and facing the right way, just wait to land
+                    ActionResult::Success => {}
+                    ActionResult::Failed(FailureReason::Landed) => return ActionResult::Success,
+                    failed => return failed,
+                }
+                // cancel the flip before rolling back over
+                if since_flip < CANCEL_DELAY + CANCEL_TIME {
+                    controller.pitch = -1.;
+                }
+            }
+        }
+        ActionResult::InProgress(ActionTickResult::from(controller))
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        vec![]
+    }
+
+    fn interruptible(&self) -> bool {
+        false
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    fn expected_duration(&self) -> Option<f32> {
+        Some(1.2)
+    }
+
+    fn name(&self) -> String {
+        String::from("HalfFlipAction")
+    }
+}
@@ -1,9 +1,10 @@
-use rlbot_lib::rlbot::{GameTickPacket, ControllerState, RenderMessage, PredictionSlice};
+use rlbot_lib::rlbot::{ControllerState, RenderMessage, PredictionSlice};
 
-use crate::utils::ActionTickResult;
+use crate::utils::{world::world::World, ActionTickResult};
 
 use super::action::{Action, ActionResult};
 
+#[derive(Clone, Debug)]
 pub struct JumpAction {
     pub duration: f32,
     timer: f32,
@@ -21,7 +22,7 @@ impl JumpAction {
 }
 
 impl Action for JumpAction {
-    fn step(&mut self, _tick_packet: GameTickPacket, controller: ControllerState, predictions: &Vec<PredictionSlice>, dt: f32) -> super::action::ActionResult {
+    fn step(&mut self, _world: &World, controller: ControllerState, _predictions: &Vec<PredictionSlice>, dt: f32) -> super::action::ActionResult {
         let jump = self.timer < self.duration;
         if !jump {
             self.counter += 1;
@@ -1,150 +1,182 @@
-use rlbot_lib::rlbot::{ControllerState, GameTickPacket, PredictionSlice, RenderMessage, Vector3};
+use rlbot_lib::rlbot::{ControllerState, PredictionSlice, RenderMessage};
 
-use crate::{
-    utils::{math::math::Vec3, ActionTickResult},
-    DEFAULT_CAR_ID,
+use crate::utils::{
+    math::math::{left_vec, Vec3},
+    world::world::World,
 };
 
 use super::{
-    action::{Action, ActionResult},
+    action::{Action, ActionResult, FailureReason, PreemptCause},
     airdodge_action::AirDodgeAction,
+    behavior_tree::{Condition, Lazy, Parallel, Sequence},
     drive_action::DriveAction,
+    speed_flip_action::SpeedFlipAction,
 };
 
+#[derive(Clone, Debug)]
 pub struct BasicKickoffAction {
     // track the progress of this action, b/c this is a timed uninterruptible action
-    current_time: f32,
-    phase: i32,
-    action: Option<Box<dyn Action>>,
-    action_state: Option<ActionResult>,
+    car_id: usize,
+    /// How fast to be going before the first flip when we start in the middle
+    pub center_speed: f32,
+    /// How fast to be going before the first flip from any other spawn
+    pub offcenter_speed: f32,
+    /// Open with a speed flip. Otherwise the first flip is a plain dodge at the ball
+    pub speed_flip: bool,
+    /// Built on the first step, so changes to the settings above still count
+    tree: Option<Sequence>,
+    /// Our speed on the last tick on the ground before the first flip
+    takeoff_speed: Option<f32>,
+    airborne: bool,
+    speed_flip_gain: Option<f32>,
 }
 
 impl BasicKickoffAction {
-    pub fn new() -> BasicKickoffAction {
+    pub const DEFAULT_CENTER_SPEED: f32 = 1550.;
+    pub const DEFAULT_OFFCENTER_SPEED: f32 = 1400.;
+
+    pub fn new(car_id: usize) -> BasicKickoffAction {
         BasicKickoffAction {
+            car_id,
             ..BasicKickoffAction::defaults()
         }
     }
     pub fn defaults() -> BasicKickoffAction {
         return BasicKickoffAction {
-            current_time: 0.,
-            phase: 0,
-            action: None,
-            action_state: None,
+            car_id: 0,
+            center_speed: BasicKickoffAction::DEFAULT_CENTER_SPEED,
+            offcenter_speed: BasicKickoffAction::DEFAULT_OFFCENTER_SPEED,
+            speed_flip: true,
+            tree: None,
+            takeoff_speed: None,
+            airborne: false,
+            speed_flip_gain: None,
         };
     }
+
+    /// How much faster the opening speed flip got us going, from the last tick on the ground
+    /// before it to the first one back down. None until it's landed, or without a speed flip
+    pub fn speed_flip_gain(&self) -> Option<f32> {
+        self.speed_flip_gain
+    }
+
+    /// Drive at the ball until we're fast enough, speed flip or dodge at the ball, land, drive
+    /// until we're close and flip into the ball
+    fn build(&self) -> Sequence {
+        let car_id = self.car_id;
+        let (center_speed, offcenter_speed) = (self.center_speed, self.offcenter_speed);
+        let speed_flip = self.speed_flip;
+        let drive_at_ball = move || -> Box<dyn Action> {
+            Box::new(Lazy::new(Box::new(move |world: &World| -> Box<dyn Action> {
+                Box::new(DriveAction::new(
+                    car_id,
+                    world.ball.location.clone(),
+                    2300.,
+                    false,
+                    false,
+                ))
+            })))
+        };
+
+        Sequence::new(vec![
+            Box::new(Parallel::any(vec![
+                drive_at_ball(),
+                Box::new(Condition::wait_until(
+                    "fast enough to flip",
+                    Box::new(move |world: &World| match world.car(car_id) {
+                        Some(car) => {
+                            let speed_threshold = if car.location.x.abs() < 100. {
+                                center_speed
+                            } else {
+                                offcenter_speed
+                            };
+                            car.velocity.norm() > speed_threshold
+                        }
+                        None => false,
+                    }),
+                )),
+            ])),
+            Box::new(Lazy::new(Box::new(move |world: &World| -> Box<dyn Action> {
+                if !speed_flip {
+                    return Box::new(AirDodgeAction::new(
+                        car_id,
+                        0.1,
+                        Some(world.ball.location.clone()),
+                    ));
+                }
+                // flip towards the side the ball's on
+                let right = world.car(car_id).map_or(true, |car| {
+                    let to_ball = world.ball.location.sub(&car.location);
+                    to_ball.dot(&left_vec(&car.rotation)) <= 0.
+                });
+                Box::new(SpeedFlipAction::new(car_id, right))
+            }))),
+            Box::new(Condition::wait_until(
+                "landed",
+                Box::new(move |world: &World| {
+                    world.car(car_id).map_or(false, |car| car.has_wheel_contact)
+                }),
+            )),
+            Box::new(Parallel::any(vec![
+                drive_at_ball(),
+                Box::new(Condition::wait_until(
+                    "close to the ball",
+                    Box::new(move |world: &World| match world.car(car_id) {
+                        Some(car) => {
+                            car.location.dist(&world.ball.location) < car.velocity.norm() * 0.3
+                        }
+                        None => false,
+                    }),
+                )),
+            ])),
+            // TODO: counter fake kickoff
+            Box::new(Lazy::new(Box::new(move |world: &World| -> Box<dyn Action> {
+                Box::new(AirDodgeAction::new(
+                    car_id,
+                    0.1,
+                    Some(world.ball.location.clone()),
+                ))
+            }))),
+        ])
+    }
 }
 
-// This is blocked on basic drive and flip actions
 impl Action for BasicKickoffAction {
     fn step(
         &mut self,
-        tick_packet: GameTickPacket,
+        world: &World,
         controller: ControllerState,
         predictions: &Vec<PredictionSlice>,
         dt: f32,
     ) -> ActionResult {
-        self.current_time += dt;
-
-        let controller = controller.clone();
-        let mut action_result = ActionTickResult::from(controller);
-        let players = tick_packet.clone().players.clone().unwrap();
-        let car = players.get(DEFAULT_CAR_ID).clone().unwrap();
-        let car_phys = car.physics.clone().unwrap();
-        let car_location = car_phys.location.clone().unwrap();
-        let car_velocity = car_phys.velocity.clone().unwrap();
-        let ball_location = tick_packet
-            .clone()
-            .ball
-            .unwrap()
-            .physics
-            .unwrap()
-            .location
-            .unwrap();
-
-        println!("Kickoff action pase: {}", self.phase);
-
-        if self.phase == 0 {
-            self.action = Some(Box::new(DriveAction::new(
-                ball_location.clone(),
-                2300.,
-                false,
-                false,
-            )));
-            self.phase = 1;
-        }
-
-        if self.phase == 1 {
-            let speed_threshold = if car_location.x.abs() < 100. {
-                1550.0
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return ActionResult::Failed(FailureReason::CarMissing),
+        };
+        // the flip itself is buried in the tree, so measure what it gained off the car
+        if self.speed_flip && self.speed_flip_gain.is_none() {
+            let speed = car.velocity.norm();
+            if !car.has_wheel_contact {
+                self.airborne = true;
+            } else if self.airborne {
+                self.speed_flip_gain = self.takeoff_speed.map(|takeoff| speed - takeoff);
             } else {
-                1400.0
-            };
-            if car_velocity.norm() > speed_threshold {
-                self.phase = 2;
-                self.action = Some(Box::new(AirDodgeAction::new(
-                    0.1,
-                    Some(car_location.add(&ball_location)),
-                )));
-                self.action_state = None;
-            }
-        }
-        if self.phase == 2 {
-            action_result.controller.boost = self.current_time < 0.1;
-
-            if car.hasWheelContact
-                && matches!(
-                    self.action_state.as_ref().unwrap_or(&ActionResult::Failed),
-                    &ActionResult::Success
-                )
-            {
-                self.action = Some(Box::new(DriveAction::new(
-                    ball_location.clone(),
-                    2300.,
-                    false,
-                    false,
-                )));
-                self.phase = 3;
+                self.takeoff_speed = Some(speed);
             }
         }
-        if self.phase == 3 {
-            if car_location.dist(&Vector3 {
-                x: 0.,
-                y: 0.,
-                z: 93.,
-            }) < car_velocity.norm() * 0.3
-            {
-                self.phase = 4;
-                self.action = Some(Box::new(AirDodgeAction::new(0.1, Some(ball_location))));
-
-                // TODO: counter fake kickoff
-            }
+        if self.tree.is_none() {
+            let mut tree = self.build();
+            tree.on_start(world);
+            self.tree = Some(tree);
         }
-
-        // tick the action:
-        if let Some(action) = self.action.as_mut() {
-            println!("ticking action: {}", action.name());
-            match action.step(
-                tick_packet.clone(),
-                action_result.controller.clone(),
-                predictions,
-                dt,
-            ) {
-                ActionResult::Success => {
-                    self.action_state = Some(ActionResult::Success);
-                    if self.phase == 4 {
-                        return ActionResult::Success;
-                    }
-                }
-                ActionResult::Failed => return ActionResult::Failed,
-                ActionResult::InProgress(mut ar) => {
-                    ar.render.append(&mut action.render());
-                    action_result = ar;
-                }
+        let tree = self.tree.as_mut().unwrap();
+        match tree.step(world, controller, predictions, dt) {
+            ActionResult::InProgress(mut res) => {
+                res.render.append(&mut tree.render());
+                ActionResult::InProgress(res)
             }
+            result => result,
         }
-
-        return ActionResult::InProgress(action_result);
     }
 
     fn render(&self) -> Vec<RenderMessage> {
@@ -160,6 +192,23 @@ impl Action for BasicKickoffAction {
     }
 
     fn name(&self) -> String {
-        format!("BasicKickoffAction (phase: {})", self.phase)
+        match self.tree.as_ref() {
+            Some(tree) => format!("BasicKickoffAction > {}", tree.name()),
+            None => String::from("BasicKickoffAction"),
+        }
+    }
+
+    fn expected_duration(&self) -> Option<f32> {
+        self.tree.as_ref().and_then(|tree| tree.expected_duration())
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.tree.as_ref().and_then(|tree| tree.progress())
+    }
+
+    fn on_interrupt(&mut self, cause: PreemptCause) {
+        if let Some(tree) = self.tree.as_mut() {
+            tree.on_interrupt(cause);
+        }
     }
 }
@@ -1,30 +1,145 @@
 // Actions
 
+pub mod aerial_action;
 pub mod airdodge_action;
+pub mod behavior_tree;
+pub mod boost_layer;
+pub mod dribble_action;
 pub mod drive_action;
+pub mod half_flip_action;
 pub mod jump_action;
 pub mod kickoff_action;
 pub mod recover_action;
 pub mod reorient_action;
-pub mod drive_shot_action;
+pub mod script_action;
+pub mod speed_flip_action;
 pub mod goto_action;
 pub mod strike;
+pub mod wavedash_action;
 
 pub mod action {
-    use rlbot_lib::rlbot::{ControllerState, GameTickPacket, RenderMessage, PredictionSlice};
+    use std::{any::Any, fmt::Debug, ops::Deref, rc::Rc};
 
-    use crate::utils::ActionTickResult;
+    use rlbot_lib::rlbot::{ControllerState, RenderMessage, PredictionSlice};
+
+    use crate::utils::{
+        channels::channels::{Channel, ALL},
+        world::world::World,
+        ActionTickResult,
+    };
 
     pub enum ActionResult {
         Success,
-        Failed,
+        Failed(FailureReason),
         InProgress(ActionTickResult),
     }
 
-    pub trait Action {
+    /// Why an action didn't finish
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum FailureReason {
+        /// The car we're controlling isn't in the packet
+        CarMissing,
+        /// Ran past the point where it could still work, like missing the intercept time
+        TimedOut,
+        /// Can't get where it wanted to go, like driving further away from the target
+        TargetUnreachable,
+        /// Came off the ground partway through something that needs the wheels down
+        LostWheelContact,
+        /// Hit the ground before an air maneuver finished
+        Landed,
+        /// Needs more boost than the car has left
+        OutOfBoost,
+        /// Lost control of the ball, like it falling off the car
+        BallLost,
+        /// An opponent came for the ball
+        Challenged,
+        /// A behavior tree Condition didn't hold
+        ConditionFailed,
+        /// The agent stopped it before it finished
+        Preempted(PreemptCause),
+    }
+
+    /// Why the agent stopped an action early
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum PreemptCause {
+        Kickoff,
+        OpponentTouch,
+        /// Ran well past its own `expected_duration`
+        Overdue,
+        /// Its `progress` stopped going down
+        Stalled,
+        /// The strategy found something better to do
+        Outscored,
+        /// The strategy reset the game state
+        Reset,
+    }
+
+    /// Every action can be cloned from behind a `Box<dyn Action>`, so a strategy can fork the
+    /// running action and try it out without touching the real one. Deriving `Clone` is enough,
+    /// this is implemented for anything that does
+    pub trait CloneAction {
+        fn clone_box(&self) -> Box<dyn Action>;
+    }
+
+    impl<T: Action + Clone + 'static> CloneAction for T {
+        fn clone_box(&self) -> Box<dyn Action> {
+            Box::new(self.clone())
+        }
+    }
+
+    impl Clone for Box<dyn Action> {
+        fn clone(&self) -> Box<dyn Action> {
+            (**self).clone_box()
+        }
+    }
+
+    /// Gets at the concrete action behind a `&dyn Action`, so a strategy can read what a finished
+    /// action found out. Implemented for every action
+    pub trait AsAny {
+        fn as_any(&self) -> &dyn Any;
+    }
+
+    impl<T: Action + 'static> AsAny for T {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    /// A closure held by an action. Clones share the closure instead of copying it, which is what
+    /// lets actions built from closures still derive `Clone` and `Debug`
+    pub struct SharedFn<F: ?Sized>(Rc<F>);
+
+    impl<F: ?Sized> Clone for SharedFn<F> {
+        fn clone(&self) -> SharedFn<F> {
+            SharedFn(self.0.clone())
+        }
+    }
+
+    impl<F: ?Sized> Debug for SharedFn<F> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "<closure>")
+        }
+    }
+
+    impl<F: ?Sized> From<Box<F>> for SharedFn<F> {
+        fn from(f: Box<F>) -> SharedFn<F> {
+            SharedFn(Rc::from(f))
+        }
+    }
+
+    impl<F: ?Sized> Deref for SharedFn<F> {
+        type Target = F;
+
+        fn deref(&self) -> &F {
+            &self.0
+        }
+    }
+
+    /// Actions are `Debug` so their whole state can go into a log when something goes wrong
+    pub trait Action: CloneAction + AsAny + Debug {
         fn step(
             &mut self,
-            tick_packet: GameTickPacket,
+            world: &World,
             controller: ControllerState,
             predictions: &Vec<PredictionSlice>,
             dt: f32,
@@ -33,5 +148,30 @@ pub mod action {
         fn interruptible(&self) -> bool;
         fn kickoff(&self) -> bool;
         fn name(&self) -> String;
+
+        /// Roughly how long this should take, in seconds. The agent drops interruptible actions
+        /// that run well past it. None means no deadline
+        fn expected_duration(&self) -> Option<f32> {
+            None
+        }
+        /// How much is left to do, in whatever unit suits the action, like distance to the target
+        /// or seconds until the intercept. Should keep going down while the action gets somewhere,
+        /// the agent drops interruptible actions when it doesn't. None if there's no good measure
+        fn progress(&self) -> Option<f32> {
+            None
+        }
+
+        /// The controller inputs this action decides. When it runs as a layer in a Blend, only
+        /// these are taken from its output. Everything by default
+        fn channels(&self) -> Vec<Channel> {
+            ALL.to_vec()
+        }
+
+        /// Called by the agent right before the first `step`
+        fn on_start(&mut self, _world: &World) {}
+        /// Called when the agent drops the action before it finishes, followed by `on_finish`
+        fn on_interrupt(&mut self, _cause: PreemptCause) {}
+        /// Called once the action is done, however that happened
+        fn on_finish(&mut self, _result: Result<(), FailureReason>) {}
     }
 }
@@ -1,23 +1,25 @@
-use rlbot_lib::rlbot::{ControllerState, GameTickPacket, Physics, RenderMessage, Vector3, PredictionSlice};
+use rlbot_lib::rlbot::{ControllerState, RenderMessage, Vector3, PredictionSlice};
 
 use crate::{
     utils::{
         arena::Arena,
         math::math::{up_vec, Vec3, vec_new, vec2_new},
         ActionTickResult, render::render::{cross, YELLOW, text},
+        world::world::{Car, World},
     },
-    DEFAULT_CAR_ID,
 };
 
 use super::{
-    action::{Action, ActionResult},
+    action::{Action, ActionResult, FailureReason},
     reorient_action::ReorientAction,
 };
 
 // NOTE: This is kinda awful, I think the coordinate system is messing with me again, but I don't
 // have time to figure it out
+#[derive(Clone, Debug)]
 pub struct RecoverAction {
     // track the progress of this action, b/c this is a timed uninterruptible action
+    car_id: usize,
     pub jump_when_upside_down: bool,
     landing_pos: Option<Vector3>,
     trajectory: Vec<Vector3>,
@@ -26,8 +28,9 @@ pub struct RecoverAction {
 }
 
 impl RecoverAction {
-    pub fn new(jump_when_upside_down: bool) -> RecoverAction {
+    pub fn new(car_id: usize, jump_when_upside_down: bool) -> RecoverAction {
         RecoverAction {
+            car_id,
             jump_when_upside_down,
             landing_pos: None,
             trajectory: vec![],
@@ -36,9 +39,9 @@ impl RecoverAction {
         }
     }
 
-    pub fn simulate_landing(&mut self, car_phys: Physics) {
-        let mut car_pos = car_phys.location.unwrap();
-        let mut car_vel = car_phys.velocity.unwrap();
+    pub fn simulate_landing(&mut self, car: &Car) {
+        let mut car_pos = car.location.clone();
+        let mut car_vel = car.velocity.clone();
         let gravity = Vector3 {
             x: 0.,
             y: 0.,
@@ -72,7 +75,7 @@ impl RecoverAction {
         if self.landing {
             let u = collision_normal.unwrap();
             let f = car_vel.sub(&u.scale(car_vel.dot(&u))).normalize();
-            self.reorient = Some(ReorientAction::from_uf(u, f, DEFAULT_CAR_ID));
+            self.reorient = Some(ReorientAction::from_uf(u, f, self.car_id));
         } else {
             let target_dir = car_vel
                 .normalize()
@@ -85,7 +88,7 @@ impl RecoverAction {
             self.reorient = Some(ReorientAction::from_uf(
                 Vector3::up(),
                 target_dir,
-                DEFAULT_CAR_ID,
+                self.car_id,
             ));
         }
     }
@@ -95,22 +98,21 @@ impl RecoverAction {
 impl Action for RecoverAction {
     fn step(
         &mut self,
-        tick_packet: GameTickPacket,
+        world: &World,
         controller: ControllerState,
- predictions: &Vec<PredictionSlice>,
+        predictions: &Vec<PredictionSlice>,
         dt: f32,
     ) -> ActionResult {
         let mut action_result = ActionTickResult::from(controller.clone());
-        let players = tick_packet.clone().players.unwrap();
-        let car = players.get(DEFAULT_CAR_ID).unwrap();
-        let car_phys = car.clone().physics.clone().unwrap();
-        // let car_location = car_phys.location.clone().unwrap();
-        let rotation = car_phys.rotation.clone().unwrap();
-        // let velocity = car_phys.velocity.clone().unwrap();
-
-        self.simulate_landing(*car_phys.clone());
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return ActionResult::Failed(FailureReason::CarMissing),
+        };
+        let rotation = car.rotation.clone();
+
+        self.simulate_landing(car);
         if let Some(reorient) = self.reorient.as_mut() {
-            match reorient.step(tick_packet.clone(), controller.clone(), predictions, dt) {
+            match reorient.step(world, controller.clone(), predictions, dt) {
                 ActionResult::InProgress(res) => {
                     action_result.controller = res.controller;
                 }
@@ -128,7 +130,7 @@ impl Action for RecoverAction {
 
         // # jump if the car is upside down and has wheel contact
         if self.jump_when_upside_down
-            && car.hasWheelContact
+            && car.has_wheel_contact
             && up_vec(&rotation).dot(&Vector3 {
                 x: 0.,
                 y: 0.,
@@ -137,7 +139,7 @@ impl Action for RecoverAction {
         {
             action_result.controller.jump = true;
             self.landing = false;
-        } else if car.hasWheelContact {
+        } else if car.has_wheel_contact {
             return ActionResult::Success;
         }
 
@@ -1,12 +1,14 @@
-use rlbot_lib::rlbot::{ControllerState, GameTickPacket, PredictionSlice, RenderMessage, Vector3};
+use rlbot_lib::rlbot::{ControllerState, PredictionSlice, RenderMessage, Vector3};
 
 use crate::utils::{
     math::math::{vec_new, Rot3, Vec3},
+    world::world::World,
     ActionTickResult,
 };
 
-use super::action::{Action, ActionResult};
+use super::action::{Action, ActionResult, FailureReason};
 
+#[derive(Clone, Debug)]
 pub struct ReorientAction {
     pub car_id: usize,
     target_up: Vector3,
@@ -28,17 +30,18 @@ impl ReorientAction {
 impl Action for ReorientAction {
     fn step(
         &mut self,
-        tick_packet: GameTickPacket,
+        world: &World,
         controller: ControllerState,
-        predictions: &Vec<PredictionSlice>,
+        _predictions: &Vec<PredictionSlice>,
         _dt: f32,
     ) -> ActionResult {
         let controller = controller.clone();
         let mut action_result = ActionTickResult::from(controller);
-        let players = tick_packet.clone().players.clone().unwrap();
-        let car = players.get(self.car_id).clone().unwrap();
-        let car_phys = car.physics.clone().unwrap();
-        let car_rotation = car_phys.rotation.clone().unwrap();
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return ActionResult::Failed(FailureReason::CarMissing),
+        };
+        let car_rotation = car.rotation.clone();
 
         let target_rot = nalgebra::Rotation3::face_towards(
             &vec_new(
@@ -61,8 +64,8 @@ impl Action for ReorientAction {
             && action_result.controller.yaw.abs() < 1e-4
         {
             ActionResult::Success
-        } else if car.hasWheelContact {
-            ActionResult::Failed
+        } else if car.has_wheel_contact {
+            ActionResult::Failed(FailureReason::Landed)
         } else {
             ActionResult::InProgress(action_result)
         }
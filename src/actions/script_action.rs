@@ -0,0 +1,197 @@
+use std::{fs, path::Path};
+
+use rlbot_lib::rlbot::{ControllerState, PredictionSlice, RenderMessage, Vector3};
+use serde::Deserialize;
+
+use crate::utils::{arena::Arena, math::math::vec_new, world::world::World};
+
+use super::{
+    action::{Action, ActionResult, PreemptCause},
+    airdodge_action::AirDodgeAction,
+    behavior_tree::{Lazy, Sequence},
+    drive_action::DriveAction,
+    jump_action::JumpAction,
+    recover_action::RecoverAction,
+};
+
+/// A maneuver written down in a RON file, see `scripts/` for examples. Coordinates are from
+/// blue's side of the field, and get mirrored when we're on orange
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Script {
+    pub name: String,
+    /// Keep running through a kickoff instead of giving way to the kickoff logic
+    #[serde(default)]
+    pub kickoff: bool,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum Step {
+    Drive {
+        target: Point,
+        speed: f32,
+        #[serde(default)]
+        walls: bool,
+        #[serde(default)]
+        slow_on_approach: bool,
+    },
+    Jump {
+        seconds: f32,
+    },
+    /// Jump for `seconds` and dodge at the target. Without one, double jump
+    Dodge {
+        seconds: f32,
+        #[serde(default)]
+        target: Option<Point>,
+    },
+    Recover {
+        #[serde(default)]
+        jump_when_upside_down: bool,
+    },
+}
+
+/// Somewhere on the field. Worked out when the step starts, not when the script is loaded
+#[derive(Clone, Debug, Deserialize)]
+pub enum Point {
+    Ball,
+    OwnGoal,
+    TheirGoal,
+    At(f32, f32, f32),
+}
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Read(std::io::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl std::fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptError::Read(e) => write!(f, "failed to read script: {e}"),
+            ScriptError::Parse(e) => write!(f, "failed to parse script: {e}"),
+        }
+    }
+}
+
+impl Script {
+    pub fn parse(text: &str) -> Result<Script, ScriptError> {
+        ron::from_str(text).map_err(ScriptError::Parse)
+    }
+
+    pub fn load(path: &Path) -> Result<Script, ScriptError> {
+        let text = fs::read_to_string(path).map_err(ScriptError::Read)?;
+        Script::parse(&text)
+    }
+}
+
+impl Point {
+    fn resolve(&self, world: &World, team: i32) -> Vector3 {
+        match self {
+            Point::Ball => world.ball.location.clone(),
+            Point::OwnGoal => Arena::home_goal_pos(team),
+            Point::TheirGoal => Arena::enemy_goal_pos(team),
+            Point::At(x, y, z) if team == 1 => vec_new(-x, -y, *z),
+            Point::At(x, y, z) => vec_new(*x, *y, *z),
+        }
+    }
+}
+
+impl Step {
+    /// The action for this step, built once the step starts
+    fn action(&self, car_id: usize) -> Box<dyn Action> {
+        let step = self.clone();
+        Box::new(Lazy::new(Box::new(move |world: &World| -> Box<dyn Action> {
+            let team = world.car(car_id).map_or(0, |car| car.team);
+            match &step {
+                Step::Drive {
+                    target,
+                    speed,
+                    walls,
+                    slow_on_approach,
+                } => Box::new(DriveAction::new(
+                    car_id,
+                    target.resolve(world, team),
+                    *speed,
+                    *walls,
+                    *slow_on_approach,
+                )),
+                Step::Jump { seconds } => Box::new(JumpAction::new(*seconds)),
+                Step::Dodge { seconds, target } => Box::new(AirDodgeAction::new(
+                    car_id,
+                    *seconds,
+                    target.as_ref().map(|target| target.resolve(world, team)),
+                )),
+                Step::Recover {
+                    jump_when_upside_down,
+                } => Box::new(RecoverAction::new(car_id, *jump_when_upside_down)),
+            }
+        })))
+    }
+}
+
+/// Runs the steps of a script one after another
+#[derive(Clone, Debug)]
+pub struct ScriptAction {
+    name: String,
+    kickoff: bool,
+    steps: Sequence,
+}
+
+impl ScriptAction {
+    pub fn new(script: &Script, car_id: usize) -> ScriptAction {
+        ScriptAction {
+            name: script.name.clone(),
+            kickoff: script.kickoff,
+            steps: Sequence::new(
+                script
+                    .steps
+                    .iter()
+                    .map(|step| step.action(car_id))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl Action for ScriptAction {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        self.steps.step(world, controller, predictions, dt)
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        self.steps.render()
+    }
+
+    fn interruptible(&self) -> bool {
+        self.steps.interruptible()
+    }
+
+    fn kickoff(&self) -> bool {
+        self.kickoff
+    }
+
+    fn name(&self) -> String {
+        format!("Script {} > {}", self.name, self.steps.name())
+    }
+
+    fn expected_duration(&self) -> Option<f32> {
+        self.steps.expected_duration()
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.steps.progress()
+    }
+
+    fn on_interrupt(&mut self, cause: PreemptCause) {
+        self.steps.on_interrupt(cause);
+    }
+}
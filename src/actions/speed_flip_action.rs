@@ -0,0 +1,120 @@
+use rlbot_lib::rlbot::{ControllerState, PredictionSlice, RenderMessage};
+
+use crate::utils::{math::math::Vec3, world::world::World, ActionTickResult};
+
+use super::action::{Action, ActionResult, FailureReason};
+
+/// How long to hold the first jump
+const JUMP_TIME: f32 = 0.1;
+/// Should have flipped and landed well before this
+const MAX_TIME: f32 = 2.;
+
+/// A diagonal front flip that gets cancelled straight away, boosting the whole time. Lands
+/// going faster than a normal flip would, and facing the same way
+#[derive(Clone, Debug)]
+pub struct SpeedFlipAction {
+    car_id: usize,
+    /// Flip forwards and to the right, otherwise forwards and to the left
+    pub right: bool,
+    timer: f32,
+    /// ticks since letting go of the first jump
+    counter: i32,
+    /// the tick we dodged on, counting the same way
+    dodged_at: Option<i32>,
+    start_speed: Option<f32>,
+    speed_gain: Option<f32>,
+}
+
+impl SpeedFlipAction {
+    pub fn new(car_id: usize, right: bool) -> SpeedFlipAction {
+        SpeedFlipAction {
+            car_id,
+            right,
+            timer: 0.,
+            counter: 0,
+            dodged_at: None,
+            start_speed: None,
+            speed_gain: None,
+        }
+    }
+
+    /// How much faster we were going when we landed than when we started, once we have
+    pub fn speed_gain(&self) -> Option<f32> {
+        self.speed_gain
+    }
+}
+
+impl Action for SpeedFlipAction {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        _predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return ActionResult::Failed(FailureReason::CarMissing),
+        };
+        let speed = car.velocity.norm();
+        let start_speed = *self.start_speed.get_or_insert(speed);
+        let side = if self.right { 1. } else { -1. };
+
+        let time = self.timer;
+        self.timer += dt;
+        if time > MAX_TIME {
+            return ActionResult::Failed(FailureReason::TimedOut);
+        }
+
+        let mut controller = controller;
+        controller.throttle = 1.;
+        controller.boost = true;
+        if time < JUMP_TIME {
+            controller.jump = true;
+            return ActionResult::InProgress(ActionTickResult::from(controller));
+        }
+
+        self.counter += 1;
+        match self.dodged_at {
+            // let go of jump for a tick, then dodge as soon as the first jump has registered
+            None => {
+                if self.counter > 1 && car.jumped && !car.double_jumped {
+                    controller.jump = true;
+                    controller.pitch = -1.;
+                    controller.yaw = side;
+                    self.dodged_at = Some(self.counter);
+                }
+            }
+            Some(dodged_at) => {
+                if car.has_wheel_contact && self.counter - dodged_at >= 2 {
+                    self.speed_gain = Some(speed - start_speed);
+                    return ActionResult::Success;
+                }
+                // cancel the flip straight away, and roll back flat for the landing
+                controller.pitch = 1.;
+                controller.roll = -side;
+            }
+        }
+        ActionResult::InProgress(ActionTickResult::from(controller))
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        vec![]
+    }
+
+    fn interruptible(&self) -> bool {
+        false
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    fn expected_duration(&self) -> Option<f32> {
+        Some(1.2)
+    }
+
+    fn name(&self) -> String {
+        String::from("SpeedFlipAction")
+    }
+}
@@ -1,37 +1,117 @@
-use rlbot_lib::rlbot::{
-    ControllerState, GameTickPacket, Physics, PredictionSlice, RenderMessage, Vector3,
-};
+use rlbot_lib::rlbot::{ControllerState, PredictionSlice, RenderMessage, Vector3};
 
-use crate::utils::{intercept::Intercept, math::math::Vec3, ActionTickResult};
+use crate::utils::{
+    intercept::{grounded_ball, Intercept, GROUNDED_BALL_HEIGHT},
+    math::math::Vec3,
+    physics::physics::{time_to_rise, BALL_RADIUS, CAR_REST_HEIGHT, JUMP_HOLD_TIME},
+    render::render::{cross, GREEN},
+    world::world::{Ball, Car, World},
+    ActionTickResult,
+};
 
 use super::{
-    action::{Action, ActionResult},
+    action::{Action, ActionResult, FailureReason, PreemptCause},
+    aerial_action::AerialAction,
+    airdodge_action::AirDodgeAction,
     goto_action::GotoAction,
+    jump_action::JumpAction,
 };
 
+/// How far below the ball's center the car's center can be and still hit it
+const REACH: f32 = 70.;
+
+/// How a strike makes contact with the ball
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StrikeKind {
+    /// Stay on the ground and drive through the ball
+    DriveThrough,
+    /// Jump into it
+    Jump,
+    /// Jump, then jump again on the way up
+    DoubleJump,
+    /// Jump and boost up to it
+    Aerial,
+}
+
+impl StrikeKind {
+    /// In order of preference, when two kinds get to the ball at the same time
+    pub const ALL: [StrikeKind; 4] = [
+        StrikeKind::DriveThrough,
+        StrikeKind::Jump,
+        StrikeKind::DoubleJump,
+        StrikeKind::Aerial,
+    ];
+
+    /// How far the car has to rise off the ground to hit `ball`
+    fn rise(ball: &Ball) -> f32 {
+        (ball.location.z - REACH - CAR_REST_HEIGHT).max(0.)
+    }
+
+    /// Can we hit `ball` this way, `time_left` seconds from now. The jumping kinds each cover
+    /// their own band of heights above the ones we can drive through, so at most one of them
+    /// makes sense for a ball. Balls on the floor by the back walls don't get any
+    pub fn feasible(&self, car: &Car, ball: &Ball, time_left: f32) -> bool {
+        let rise = StrikeKind::rise(ball);
+        match self {
+            StrikeKind::DriveThrough => grounded_ball(car, ball, time_left),
+            StrikeKind::Jump => {
+                ball.location.z > GROUNDED_BALL_HEIGHT
+                    && time_to_rise(rise, false).map_or(false, |t| t <= time_left)
+            }
+            StrikeKind::DoubleJump => {
+                time_to_rise(rise, false).is_none()
+                    && time_to_rise(rise, true).map_or(false, |t| t <= time_left)
+            }
+            StrikeKind::Aerial => {
+                time_to_rise(rise, true).is_none()
+                    && AerialAction::reachable(car, &ball.location, time_left)
+            }
+        }
+    }
+
+    /// How many seconds before contact to leave the ground, None for staying on it. Aerials
+    /// are planned assuming we take off right away
+    fn takeoff_time(&self, ball: &Ball, time_left: f32) -> Option<f32> {
+        match self {
+            StrikeKind::DriveThrough => None,
+            StrikeKind::Jump => time_to_rise(StrikeKind::rise(ball), false),
+            StrikeKind::DoubleJump => time_to_rise(StrikeKind::rise(ball), true),
+            StrikeKind::Aerial => Some(time_left),
+        }
+    }
+}
+
+/// Hits the ball where our intercept says we can reach it, refreshing the intercept as we go.
+/// The kind decides how we make contact once we're there
+#[derive(Clone, Debug)]
 pub struct StrikeAction {
+    pub kind: StrikeKind,
+    /// Where we'd like the ball to go, None to just hit it
     pub target: Option<Vector3>,
     pub goto: Option<GotoAction>,
     pub intercept: Option<Intercept>,
     pub last_update_time: f32,
-    pub finished: bool,
     update_interval: f32,
+    /// Stop changing plans this many seconds before contact
     stop_updating: f32,
     max_additional_time: f32,
     car_id: usize,
-    initial_time: f32,
-    configure: Option<Box<dyn Fn(Vector3, &mut GotoAction, &Intercept)>>,
-    use_intercept_predicate: bool,
+    /// secondsElapsed on our first step
+    start_time: Option<f32>,
+    /// Passed on to the GotoAction we drive with
+    pub goto_lerp_t: f32,
+    /// seconds until the intercept as of the last step
+    time_left: Option<f32>,
+    /// The jump, once we've taken off
+    contact: Option<Box<dyn Action>>,
+    /// and whether it's done, so we're just waiting to hit the ball
+    contact_done: bool,
 }
 
 impl StrikeAction {
-    pub fn new(
-        car_id: usize,
-        target: Option<Vector3>,
-        configure: Option<Box<dyn Fn(Vector3, &mut GotoAction, &Intercept)>>,
-        use_intercept_predicate: bool,
-    ) -> StrikeAction {
+    pub fn new(car_id: usize, kind: StrikeKind, target: Option<Vector3>) -> StrikeAction {
         StrikeAction {
+            kind,
             update_interval: 0.2,
             stop_updating: 0.1,
             max_additional_time: 0.4,
@@ -40,21 +120,54 @@ impl StrikeAction {
             goto: None,
             intercept: None,
             last_update_time: 0.,
-            initial_time: -1.,
-            finished: true,
-            configure,
-            use_intercept_predicate,
+            start_time: None,
+            goto_lerp_t: GotoAction::DEFAULT_LERP_T,
+            time_left: None,
+            contact: None,
+            contact_done: false,
         }
     }
 
+    pub fn with_lerp_t(mut self, lerp_t: f32) -> StrikeAction {
+        self.goto_lerp_t = lerp_t;
+        if let Some(goto) = self.goto.as_mut() {
+            goto.lerp_t = lerp_t;
+        }
+        self
+    }
+
+    /// The strike that gets to the ball soonest, out of every kind we can pull off
+    pub fn choose(
+        car_id: usize,
+        world: &World,
+        predictions: &Vec<PredictionSlice>,
+        target: Option<Vector3>,
+    ) -> Option<StrikeAction> {
+        StrikeKind::ALL
+            .iter()
+            .filter_map(|kind| {
+                let mut strike = StrikeAction::new(car_id, *kind, target.clone());
+                strike.update_intercept(world, predictions);
+                match strike.intercept.as_ref() {
+                    Some(incpt) if incpt.is_viable => Some(strike),
+                    _ => None,
+                }
+            })
+            .min_by(|a, b| {
+                let a = a.intercept.as_ref().unwrap().game_seconds;
+                let b = b.intercept.as_ref().unwrap().game_seconds;
+                a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
+
     pub fn best_target(
-        car_phys: Physics,
-        ball: Physics,
+        car: &Car,
+        ball: &Ball,
         their_goal: &Vector3,
         targets: &Vec<Vector3>,
     ) -> Option<Vector3> {
-        let car_loc = &car_phys.location.unwrap().ground();
-        let ball_loc = ball.location.unwrap().ground();
+        let car_loc = &car.location.ground();
+        let ball_loc = ball.location.ground();
         let to_goal = their_goal.ground().sub(&car_loc);
         targets
             .iter()
@@ -74,126 +187,213 @@ impl StrikeAction {
             .cloned()
     }
 
+    /// Which way we want to send the ball from the intercept, on the ground
+    fn shot_direction(&self, intercept: &Intercept) -> Option<Vector3> {
+        self.target
+            .as_ref()
+            .map(|target| target.ground().sub(&intercept.location.ground()).normalize())
+    }
+
     /// setup the goto action based on our intercept
-    pub fn default_configure(&mut self, intercept: Intercept) {
-        match self.goto.as_mut() {
-            Some(goto) => {
-                goto.target = intercept.location.clone();
-                goto.arrival_time = intercept.time;
-            }
-            None => {
-                self.goto = Some(GotoAction::new(intercept.location.clone(), None, self.car_id));
-            }
+    pub fn configure(&mut self, intercept: Intercept) {
+        // line up behind the ball, so we hit it towards the target
+        let mut contact = intercept.location.ground();
+        if let Some(direction) = self.shot_direction(&intercept) {
+            contact = contact.sub(&direction.scale(BALL_RADIUS));
         }
+        contact.z = CAR_REST_HEIGHT;
+
+        let car_id = self.car_id;
+        let direction = self.shot_direction(&intercept);
+        let goto = self
+            .goto
+            .get_or_insert_with(|| GotoAction::new(contact.clone(), None, car_id));
+        goto.target = contact;
+        goto.target_direction = direction;
+        goto.arrival_time = intercept.game_seconds;
+        goto.lerp_t = self.goto_lerp_t;
 
         self.intercept = Some(intercept);
     }
 
-    pub fn update_intercept(
-        &mut self,
-        tick_packet: &GameTickPacket,
-        predictions: &Vec<PredictionSlice>,
-    ) {
-        let game_time = tick_packet.gameInfo.clone().unwrap().secondsElapsed;
-        if self.initial_time == -1. {
-            self.initial_time = game_time;
-        }
-
-        let players = tick_packet.clone().players.unwrap();
-        let car = players.get(self.car_id).unwrap();
-        let car_phys = car.physics.clone().unwrap();
-        let _car_location = car_phys.location.clone().unwrap();
-        let _rotation = car_phys.rotation.clone().unwrap();
-        let _velocity = car_phys.velocity.clone().unwrap();
-        let ball = tick_packet.ball.clone().unwrap().physics.unwrap();
-        let intercept = Intercept::new(&car, game_time, &predictions, *ball, false, self.use_intercept_predicate);
-
-        if let Some(conf) = self.configure.as_ref() {
-            let mut goto = self.goto.clone();
-            conf(self.target.clone().unwrap(), goto.as_mut().unwrap(), &intercept);
-            self.goto = goto;
-        } else {
-            self.default_configure(intercept);
-        }
+    pub fn update_intercept(&mut self, world: &World, predictions: &Vec<PredictionSlice>) {
+        let game_time = world.game.seconds_elapsed;
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return,
+        };
+        let kind = self.kind;
+        let intercept = Intercept::new(
+            car,
+            game_time,
+            predictions,
+            &world.ball,
+            // aerials don't drive there, so the drive time doesn't matter
+            kind == StrikeKind::Aerial,
+            Some(&|car: &Car, ball: &Ball, time_left: f32| kind.feasible(car, ball, time_left)),
+        );
+        self.configure(intercept);
         self.last_update_time = game_time;
-        if let Some(incpt) = self.intercept.as_ref() {
-            if !incpt.is_viable || incpt.time > self.initial_time + self.max_additional_time {
-                self.finished = true;
-            }
-        }
+    }
+
+    /// Leave the ground the way our kind does
+    fn take_off(&mut self, intercept: &Intercept) {
+        self.contact = match self.kind {
+            StrikeKind::DriveThrough => None,
+            StrikeKind::Jump => Some(Box::new(JumpAction::new(JUMP_HOLD_TIME))),
+            StrikeKind::DoubleJump => Some(Box::new(AirDodgeAction::new(
+                self.car_id,
+                JUMP_HOLD_TIME,
+                None,
+            ))),
+            StrikeKind::Aerial => Some(Box::new(
+                AerialAction::new(self.car_id)
+                    .with_target(intercept.location.clone(), intercept.game_seconds),
+            )),
+        };
     }
 }
 
 impl Action for StrikeAction {
     fn step(
         &mut self,
-        tick_packet: GameTickPacket,
+        world: &World,
         controller: ControllerState,
         predictions: &Vec<PredictionSlice>,
         dt: f32,
     ) -> ActionResult {
-        if self.intercept.is_none() {
-            return ActionResult::Failed;
+        let game_time = world.game.seconds_elapsed;
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return ActionResult::Failed(FailureReason::CarMissing),
+        };
+        let start_time = *self.start_time.get_or_insert(game_time);
+
+        // we got a touch in, that's all we were here for
+        if let Some(touch) = world.ball.latest_touch.as_ref() {
+            if touch.player_index == self.car_id && touch.game_seconds >= start_time {
+                return ActionResult::Success;
+            }
         }
-        let mut action_result = ActionTickResult::from(controller);
-        let incpt = self.intercept.clone().unwrap();
-        let game_time = tick_packet.gameInfo.clone().unwrap().secondsElapsed;
-        let players = tick_packet.clone().players.unwrap();
-        let car = players.get(self.car_id).unwrap();
-        let car_phys = car.physics.clone().unwrap();
-        let _car_location = car_phys.location.clone().unwrap();
-        let _rotation = car_phys.rotation.clone().unwrap();
-        let _velocity = car_phys.velocity.clone().unwrap();
-        let _ball = tick_packet.ball.clone().unwrap().physics.unwrap();
-
-        if self.last_update_time + self.update_interval < game_time
-            && game_time < incpt.time - self.stop_updating
-            && car.hasWheelContact
-            && !action_result.controller.jump
+
+        let time_left = self.time_left.unwrap_or(f32::MAX);
+        if self.intercept.is_none()
+            || (self.contact.is_none()
+                && car.has_wheel_contact
+                && self.last_update_time + self.update_interval < game_time
+                && time_left > self.stop_updating)
         {
-            println!("update_intercept");
-            self.update_intercept(&tick_packet, predictions);
+            self.update_intercept(world, predictions);
+        }
+        let incpt = match self.intercept.clone() {
+            Some(incpt) if incpt.is_viable => incpt,
+            _ => return ActionResult::Failed(FailureReason::TargetUnreachable),
+        };
+        let time_left = incpt.game_seconds - game_time;
+        self.time_left = Some(time_left);
+
+        // the ball should have been hit by now
+        if time_left < -self.max_additional_time {
+            return ActionResult::Failed(FailureReason::TimedOut);
+        }
+
+        if self.contact.is_none() {
+            // knocked into the air well before we get there
+            if !car.has_wheel_contact && time_left > 1. {
+                return ActionResult::Failed(FailureReason::LostWheelContact);
+            }
+            if let Some(takeoff) = self.kind.takeoff_time(&incpt.ball, time_left) {
+                if time_left <= takeoff + dt {
+                    // jumping from the wrong spot just wastes the jump
+                    let landing = car.location.add(&car.velocity.scale(time_left));
+                    let target = self.goto.as_ref().map_or(incpt.location.clone(), |goto| {
+                        goto.target.clone()
+                    });
+                    if self.kind != StrikeKind::Aerial
+                        && landing.ground_dist(&target) > BALL_RADIUS * 1.5
+                    {
+                        return ActionResult::Failed(FailureReason::TargetUnreachable);
+                    }
+                    self.take_off(&incpt);
+                }
+            }
         }
-        if incpt.time - game_time > 1. && self.interruptible() && !car.hasWheelContact {
-            self.finished = true;
+
+        if let Some(contact) = self.contact.as_mut() {
+            if self.contact_done {
+                return ActionResult::InProgress(ActionTickResult::from(controller));
+            }
+            return match contact.step(world, controller.clone(), predictions, dt) {
+                ActionResult::Success => {
+                    self.contact_done = true;
+                    ActionResult::InProgress(ActionTickResult::from(controller))
+                }
+                result => result,
+            };
         }
+
+        let mut action_result = ActionTickResult::from(controller.clone());
         if let Some(goto) = self.goto.as_mut() {
-            match goto.step(tick_packet, action_result.controller.clone(), predictions, dt) {
+            match goto.step(world, controller, predictions, dt) {
                 ActionResult::InProgress(res) => {
                     action_result.controller = res.controller;
                     if goto.drive.target_speed < 300. {
                         action_result.controller.throttle = 0.;
                     };
                 }
-                _ => {
-                    self.finished = true;
-                }
+                // there already, keep going straight through the ball
+                ActionResult::Success => action_result.controller.throttle = 1.,
+                failed => return failed,
             }
         }
-        if self.finished {
-            ActionResult::Success
-        } else {
-            ActionResult::InProgress(action_result)
-        }
+        ActionResult::InProgress(action_result)
     }
 
     fn render(&self) -> Vec<RenderMessage> {
-        vec![]
+        let mut renders = match self.contact.as_ref() {
+            Some(contact) => contact.render(),
+            None => vec![],
+        };
+        if let Some(incpt) = self.intercept.as_ref() {
+            renders.append(&mut cross(&incpt.location, 150., GREEN));
+        }
+        renders
     }
 
+    /// Not once we've left the ground
     fn interruptible(&self) -> bool {
-        if let Some(goto) = self.goto.as_ref() {
-            goto.interruptible()
-        } else {
-            true
-        }
+        self.contact.is_none()
     }
 
     fn kickoff(&self) -> bool {
         false
     }
 
+    /// Until the intercept, plus the slack we give ourselves for hitting it late
+    fn expected_duration(&self) -> Option<f32> {
+        match (self.intercept.as_ref(), self.start_time) {
+            (Some(incpt), Some(start)) => {
+                Some(incpt.game_seconds - start + self.max_additional_time)
+            }
+            _ => None,
+        }
+    }
+
+    /// Stops going down when the intercept keeps slipping further away
+    fn progress(&self) -> Option<f32> {
+        self.time_left
+    }
+
     fn name(&self) -> String {
-        String::from("StrikeAction")
+        match self.intercept.as_ref() {
+            Some(incpt) => format!("StrikeAction ({:?} at {:.2})", self.kind, incpt.game_seconds),
+            None => format!("StrikeAction ({:?})", self.kind),
+        }
+    }
+
+    fn on_interrupt(&mut self, cause: PreemptCause) {
+        if let Some(contact) = self.contact.as_mut() {
+            contact.on_interrupt(cause);
+        }
     }
 }
@@ -0,0 +1,139 @@
+use rlbot_lib::rlbot::{ControllerState, PredictionSlice, RenderMessage};
+
+use crate::utils::{
+    math::math::{forward_vec, up_vec, Vec3},
+    physics::physics::CAR_REST_HEIGHT,
+    world::world::{Car, World},
+    ActionTickResult,
+};
+
+use super::action::{Action, ActionResult, FailureReason};
+
+/// How long to hold jump, just enough to hop
+const JUMP_TIME: f32 = 0.05;
+/// How long we get to leave the ground after letting go of jump
+const LIFTOFF_TIME: f32 = 0.1;
+/// Dodge once the car is this close to landing on the way down
+const DODGE_HEIGHT: f32 = 10.;
+/// How far to pull the nose up on the way up, so we land on the back wheels
+const TILT: f32 = 0.5;
+/// Should be back on the ground well before this
+const MAX_TIME: f32 = 1.5;
+
+/// A tiny hop, then a forward dodge just as the wheels touch back down. The dodge turns into
+/// speed instead of a flip
+#[derive(Clone, Debug)]
+pub struct WavedashAction {
+    car_id: usize,
+    timer: f32,
+    /// ticks since the dodge, once we've done it
+    since_dodge: Option<i32>,
+    start_speed: Option<f32>,
+    speed_gain: Option<f32>,
+}
+
+impl WavedashAction {
+    pub fn new(car_id: usize) -> WavedashAction {
+        WavedashAction {
+            car_id,
+            timer: 0.,
+            since_dodge: None,
+            start_speed: None,
+            speed_gain: None,
+        }
+    }
+
+    /// How much faster we were going when we landed than when we started, once we have
+    pub fn speed_gain(&self) -> Option<f32> {
+        self.speed_gain
+    }
+
+    /// Whether a wavedash is a good way to speed up, heading straight at something `dist` away
+    /// that we want to get to at `target_speed`. Boosting is better when we've got any
+    pub fn worth_it(car: &Car, dist: f32, target_speed: f32) -> bool {
+        let forward_vel = car.velocity.dot(&forward_vec(&car.rotation));
+        car.has_wheel_contact
+            && up_vec(&car.rotation).z > 0.95
+            && car.boost < 10
+            && dist > 2000.
+            && forward_vel > 800.
+            && forward_vel < 1900.
+            && target_speed > forward_vel + 300.
+    }
+}
+
+impl Action for WavedashAction {
+    fn step(
+        &mut self,
+        world: &World,
+        controller: ControllerState,
+        _predictions: &Vec<PredictionSlice>,
+        dt: f32,
+    ) -> ActionResult {
+        let car = match world.car(self.car_id) {
+            Some(car) => car,
+            None => return ActionResult::Failed(FailureReason::CarMissing),
+        };
+        let speed = car.velocity.norm();
+        let start_speed = *self.start_speed.get_or_insert(speed);
+
+        let time = self.timer;
+        self.timer += dt;
+        if time > MAX_TIME {
+            return ActionResult::Failed(FailureReason::TimedOut);
+        }
+
+        let mut controller = controller;
+        controller.throttle = 1.;
+        controller.boost = false;
+        if time < JUMP_TIME {
+            controller.jump = true;
+            return ActionResult::InProgress(ActionTickResult::from(controller));
+        }
+
+        match self.since_dodge.as_mut() {
+            None => {
+                if car.has_wheel_contact {
+                    // either never got off the ground, or came down without dodging
+                    if time > JUMP_TIME + LIFTOFF_TIME || !car.jumped {
+                        return ActionResult::Failed(FailureReason::Landed);
+                    }
+                } else if car.velocity.z < 0. && car.location.z - CAR_REST_HEIGHT < DODGE_HEIGHT {
+                    controller.jump = true;
+                    controller.pitch = -1.;
+                    self.since_dodge = Some(0);
+                } else if car.velocity.z > 0. {
+                    controller.pitch = TILT;
+                }
+            }
+            Some(since_dodge) => {
+                *since_dodge += 1;
+                if car.has_wheel_contact && *since_dodge >= 2 {
+                    self.speed_gain = Some(speed - start_speed);
+                    return ActionResult::Success;
+                }
+            }
+        }
+        ActionResult::InProgress(ActionTickResult::from(controller))
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        vec![]
+    }
+
+    fn interruptible(&self) -> bool {
+        false
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    fn expected_duration(&self) -> Option<f32> {
+        Some(0.8)
+    }
+
+    fn name(&self) -> String {
+        String::from("WavedashAction")
+    }
+}
@@ -5,20 +5,45 @@
 
 pub mod bot {
     use rlbot_lib::rlbot::{
-        ControllerState, GameTickPacket, Physics, PlayerInput, PredictionSlice, RenderMessage,
+        ControllerState, DesiredGameState, GameTickPacket, PlayerInput, PredictionSlice,
+        RenderMessage,
     };
 
-    use crate::actions::action::{Action, ActionResult};
-    use crate::strategies::strategy::Strategy;
+    use crate::actions::action::{Action, ActionResult, FailureReason, PreemptCause};
+    use crate::strategies::strategy::{best, Attempt, ResetTrigger, Strategy};
     use crate::utils::math::math::{dir_vecs, vec2_new, Vec3};
     use crate::utils::render::render::{line, text, BLUE, GREEN, RED, YELLOW};
+    use crate::utils::world::world::{Car, World};
     use crate::utils::AgentTickResult;
 
+    /// Drop an action once it's taken this many times longer than it expected to
+    const DEADLINE_SLACK: f32 = 1.5;
+    /// Drop an action when its progress hasn't improved for this many seconds
+    const STALL_TIME: f32 = 1.;
+
+    /// The ball counts as stopped below this speed
+    const BALL_STOPPED_SPEED: f32 = 20.;
+    /// and after staying that slow for this long
+    const BALL_STOPPED_TIME: f32 = 1.;
+
+    /// Keeps track of the current attempt, so we know when the strategy wants a reset
+    #[derive(Default)]
+    struct AttemptState {
+        number: u32,
+        /// secondsElapsed when the attempt started, None until the first tick we act on
+        start: Option<f32>,
+        /// the last score we saw, so we notice goals
+        score: Option<[i32; 2]>,
+        ball_moved: bool,
+        /// how long the ball has been sitting still
+        ball_still_time: f32,
+    }
+
     pub struct Agent {
         // pub player_config: PlayerConfiguration, // I'm not sure what this is used for
         pub debug_rendering: bool,
-        /// The last known car Physics Object
-        phys: Option<Box<Physics>>,
+        /// The last known state of our car
+        car: Option<Car>,
         pub car_id: usize,
         last_touch_time: Option<f32>,
         /// Used to ignore the first 20 ticks (which aren't useful or something)
@@ -29,23 +54,37 @@ pub mod bot {
         current_action: Option<Box<dyn Action>>,
         /// how long we've been trying to do the same thing
         action_timer: f32,
+        /// the lowest `progress` the current action has reported
+        best_progress: Option<f32>,
+        /// action_timer when best_progress last went down
+        last_progress_time: f32,
+        /// Label of the candidate the current action came from, if it came from one
+        current_label: Option<&'static str>,
+        /// and its utility, as of the last tick it was scored
+        current_utility: f32,
         pub strategy: Box<dyn Strategy>,
         current_controller: ControllerState,
+        attempt: AttemptState,
     }
 
     impl Agent {
-        pub fn new(debug: bool, car_id: usize, strategy: impl Strategy + 'static) -> Agent {
+        pub fn new(debug: bool, car_id: usize, strategy: Box<dyn Strategy>) -> Agent {
             Agent {
                 debug_rendering: debug,
-                phys: None,
+                car: None,
                 car_id,
                 last_touch_time: None,
                 tick_count: 0,
                 last_tick_time: 0.,
                 current_action: None,
                 action_timer: 0.,
-                strategy: Box::new(strategy),
+                best_progress: None,
+                last_progress_time: 0.,
+                current_label: None,
+                current_utility: 0.,
+                strategy,
                 current_controller: ControllerState::default(),
+                attempt: AttemptState::default(),
             }
         }
 
@@ -57,74 +96,116 @@ pub mod bot {
             // Ignore the first 20 ticks
             if self.tick_count < 20 {
                 self.tick_count += 1;
-                return AgentTickResult::from(PlayerInput {
-                    playerIndex: self.car_id as i32,
-                    controllerState: Some(Box::new(ControllerState::default())),
-                });
+                return self.idle();
             };
 
-            let ball = packet.clone().ball.unwrap();
-            let _target = ball.clone().physics.unwrap();
-            let tmp = packet.clone().players.unwrap();
-            let car = tmp.get(self.car_id).expect("There is no game.");
-            let _boost = car.boost;
-            self.phys = car.physics.clone();
-            let car_phys = car.physics.clone().unwrap();
-            let seconds_elapsed = packet.gameInfo.clone().unwrap().secondsElapsed;
+            // parse the packet once, everything below works off of this snapshot
+            let world = match World::from_packet(&packet) {
+                Some(world) => world,
+                None => return self.idle(),
+            };
+            let car = match world.car(self.car_id) {
+                Some(car) => car.clone(),
+                None => return self.idle(),
+            };
+            self.car = Some(car.clone());
+            let seconds_elapsed = world.game.seconds_elapsed;
             let dt = seconds_elapsed - self.last_tick_time;
             self.action_timer += dt;
             self.last_tick_time = seconds_elapsed;
-            let is_kickoff = packet.gameInfo.clone().unwrap().isKickoffPause;
-            // cancel current_action if a kickoff is happening and current_action isn't a kickoff
+            let is_kickoff = world.game.is_kickoff_pause;
+            // keep track of whether the current action is getting anywhere
+            if let Some(progress) = self.current_action.as_ref().and_then(|a| a.progress()) {
+                if self.best_progress.map_or(true, |best| progress < best) {
+                    self.best_progress = Some(progress);
+                    self.last_progress_time = self.action_timer;
+                }
+            }
+
+            // cancel current_action if a kickoff is happening and current_action isn't a kickoff,
+            // or if it's running late or going nowhere
+            let mut preempt = None;
             if let Some(action) = &self.current_action {
+                let overdue = action
+                    .expected_duration()
+                    .map_or(false, |duration| self.action_timer > duration * DEADLINE_SLACK);
+                let stalled = action.progress().is_some()
+                    && self.action_timer - self.last_progress_time > STALL_TIME;
                 if is_kickoff && !action.kickoff() {
-                    println!("Clearing for kickoff");
-                    self.current_action = None;
-                    self.action_timer = 0.;
-                } else if self.action_timer > 2. && action.interruptible() {
-                    // HACK: stale actions are really just a band-aid for other problems
-                    println!("Stale action");
-                    self.current_action = None;
-                    self.action_timer = 0.;
+                    preempt = Some(PreemptCause::Kickoff);
+                } else if overdue && action.interruptible() {
+                    preempt = Some(PreemptCause::Overdue);
+                } else if stalled && action.interruptible() {
+                    preempt = Some(PreemptCause::Stalled);
                 }
             }
 
             // reset action when another car hits the ball
-            if let Some(touch) = ball.latestTouch.clone() {
+            if let Some(touch) = world.ball.latest_touch.as_ref() {
                 if let Some(last_touch) = self.last_touch_time {
-                    if touch.gameSeconds > last_touch
-                        && touch.playerName.unwrap_or(String::from(""))
-                            != packet.players.clone().unwrap()[self.car_id]
-                                .name
-                                .clone()
-                                .unwrap_or(String::from(" "))
-                    {
-                        self.last_touch_time = Some(touch.gameSeconds);
+                    if touch.game_seconds > last_touch && touch.player_index != self.car_id {
+                        self.last_touch_time = Some(touch.game_seconds);
 
                         // don't reset when we're dodging, wavedashing or recovering
                         if let Some(action) = &self.current_action {
                             if action.interruptible() {
-                                self.current_action = None;
-                                self.action_timer = 0.;
+                                preempt = preempt.or(Some(PreemptCause::OpponentTouch));
                             }
                         }
                     }
                 }
             }
+            if let Some(cause) = preempt {
+                self.preempt(cause);
+            }
+
+            // score what we could be doing, and switch when something beats the current plan
+            let candidates = self.strategy.candidates(
+                &world,
+                self.car_id,
+                ball_predictions,
+                is_kickoff,
+                self.current_action.as_deref(),
+            );
+            // a plan nobody's offering any more isn't worth anything
+            self.current_utility = candidates
+                .iter()
+                .find(|candidate| Some(candidate.label) == self.current_label)
+                .map_or(0., |current| current.utility);
+            if let Some(candidate) = best(candidates) {
+                let switch = match &self.current_action {
+                    None => true,
+                    Some(action) => {
+                        action.interruptible()
+                            && Some(candidate.label) != self.current_label
+                            && candidate.utility
+                                > self.current_utility + self.strategy.switch_margin()
+                    }
+                };
+                if switch {
+                    if self.current_action.is_some() {
+                        self.preempt(PreemptCause::Outscored);
+                    }
+                    self.start(Some(candidate.action), &world);
+                    self.current_label = Some(candidate.label);
+                    self.current_utility = candidate.utility;
+                }
+            }
 
             // choose action
             if self.current_action.is_none() {
                 println!("Assigning new Action");
-                self.current_action =
-                    self.strategy
-                        .choose_action(packet.clone(), ball_predictions, is_kickoff);
-                if let Some(action) = &self.current_action {
-                    println!("Choosen Action: {}", action.name());
-                }
+                let action = self.strategy.choose_action(
+                    &world,
+                    self.car_id,
+                    ball_predictions,
+                    is_kickoff,
+                );
+                self.start(action, &world);
             }
 
-            let vecs = dir_vecs(&car_phys.rotation.clone().unwrap());
-            let car_loc = car_phys.location.clone().unwrap();
+            let vecs = dir_vecs(&car.rotation);
+            let car_loc = car.location.clone();
             let mut controller = self.current_controller.clone();
             let scale = 150.;
             let mut renders: Vec<RenderMessage> = vec![
@@ -133,24 +214,30 @@ pub mod bot {
                 line(&car_loc, &car_loc.add(&vecs[2].scale(scale)), RED),
             ];
 
+            let action_name = self.current_action.as_ref().map(|action| action.name());
+            // set when the action finished this tick
+            let mut finished = None;
             if let Some(action) = self.current_action.as_mut() {
-                if let ActionResult::InProgress(mut res) =
-                    action.step(packet.clone(), controller.clone(), ball_predictions, dt)
-                {
-                    controller = res.controller;
-                    renders.append(&mut res.render);
-
-                    if self.debug_rendering {
-                        renders.append(&mut action.render());
-                        renders.push(text(&vec2_new(20., 20.), action.name(), YELLOW));
+                match action.step(&world, controller.clone(), ball_predictions, dt) {
+                    ActionResult::InProgress(mut res) => {
+                        controller = res.controller;
+                        renders.append(&mut res.render);
+
+                        if self.debug_rendering {
+                            renders.append(&mut action.render());
+                            renders.push(text(&vec2_new(20., 20.), action.name(), YELLOW));
+                        }
                     }
-                } else {
-                    self.current_action = None;
-                    self.action_timer = 0.;
+                    ActionResult::Success => finished = Some(Ok(())),
+                    ActionResult::Failed(reason) => finished = Some(Err(reason)),
                 }
             }
+            if let Some(result) = finished {
+                self.finish(result);
+            }
 
             self.current_controller = controller;
+            let game_state = self.check_reset(&world, dt, finished, &action_name);
 
             AgentTickResult {
                 input: PlayerInput {
@@ -158,7 +245,117 @@ pub mod bot {
                     controllerState: Some(Box::new(self.current_controller.clone())),
                 },
                 render: renders,
+                action: action_name,
+                game_state,
+            }
+        }
+
+        /// Ask the strategy for a new game state if anything in its reset policy happened this
+        /// tick
+        fn check_reset(
+            &mut self,
+            world: &World,
+            dt: f32,
+            finished: Option<Result<(), FailureReason>>,
+            action_name: &Option<String>,
+        ) -> Option<DesiredGameState> {
+            let policy = self.strategy.reset_policy();
+            let now = world.game.seconds_elapsed;
+            let attempt = &mut self.attempt;
+            let start = *attempt.start.get_or_insert(now);
+
+            let scored = attempt.score.map_or(false, |score| score != world.game.score);
+            attempt.score = Some(world.game.score);
+
+            if world.ball.velocity.norm() > BALL_STOPPED_SPEED {
+                attempt.ball_moved = true;
+                attempt.ball_still_time = 0.;
+            } else {
+                attempt.ball_still_time += dt;
+            }
+
+            let trigger = if let (true, Some(Ok(()))) = (policy.on_success, finished) {
+                ResetTrigger::ActionSucceeded
+            } else if let (true, Some(Err(reason))) = (policy.on_failure, finished) {
+                ResetTrigger::ActionFailed(reason)
+            } else if policy.on_goal && scored {
+                ResetTrigger::Goal
+            } else if policy.on_ball_stopped
+                && attempt.ball_moved
+                && attempt.ball_still_time > BALL_STOPPED_TIME
+            {
+                ResetTrigger::BallStopped
+            } else if policy.timeout.map_or(false, |timeout| now - start > timeout) {
+                ResetTrigger::Timeout
+            } else {
+                return None;
+            };
+
+            let previous = Attempt {
+                number: attempt.number,
+                trigger,
+                duration: now - start,
+                action: action_name.clone(),
+            };
+            *attempt = AttemptState {
+                number: attempt.number + 1,
+                start: Some(now),
+                score: attempt.score,
+                ..Default::default()
+            };
+            // whatever we were doing was for the old game state
+            self.preempt(PreemptCause::Reset);
+            self.strategy.set_game_state(self.car_id, &previous)
+        }
+
+        /// Make `action` the current action
+        fn start(&mut self, action: Option<Box<dyn Action>>, world: &World) {
+            self.current_action = action;
+            self.current_label = None;
+            self.current_utility = 0.;
+            if let Some(action) = self.current_action.as_mut() {
+                println!("Choosen Action: {}", action.name());
+                action.on_start(world);
+                self.action_timer = 0.;
+                self.best_progress = None;
+                self.last_progress_time = 0.;
             }
         }
+
+        /// Drop the current action before it finishes
+        fn preempt(&mut self, cause: PreemptCause) {
+            if let Some(action) = self.current_action.as_mut() {
+                println!("Interrupting {}: {cause:?}", action.name());
+                action.on_interrupt(cause);
+                self.finish(Err(FailureReason::Preempted(cause)));
+            }
+        }
+
+        /// Wrap up the current action, and let it and the strategy know how it went
+        fn finish(&mut self, result: Result<(), FailureReason>) {
+            if let Some(mut action) = self.current_action.take() {
+                if let Err(reason) = result {
+                    println!("{} failed: {reason:?}", action.name());
+                    // with debug on, dump everything the action knew when it gave up
+                    if self.debug_rendering && !matches!(reason, FailureReason::Preempted(_)) {
+                        println!("{action:?}");
+                    }
+                }
+                action.on_finish(result);
+                self.strategy.on_action_finished(action.as_ref(), result);
+            }
+            self.action_timer = 0.;
+            self.best_progress = None;
+            self.last_progress_time = 0.;
+            self.current_label = None;
+        }
+
+        /// Neutral inputs for ticks where there's nothing to act on
+        fn idle(&self) -> AgentTickResult {
+            AgentTickResult::from(PlayerInput {
+                playerIndex: self.car_id as i32,
+                controllerState: Some(Box::new(ControllerState::default())),
+            })
+        }
     }
 }
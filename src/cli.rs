@@ -1,6 +1,8 @@
 /// Handle the CLI with CLAP
 
 pub mod cli {
+    use std::path::PathBuf;
+
     use clap::Parser;
 
     /// Control RocketLeague to create a game and start different bots.
@@ -11,8 +13,60 @@ pub mod cli {
         #[arg(short, long)]
         pub start: bool,
 
-        /// Run the current test
-        #[arg(short, long)]
-        pub test: bool
+        /// Run the current test, same as `--strategy test`
+        #[arg(short, long, conflicts_with = "strategy")]
+        pub test: bool,
+
+        /// Strategy to play with, overrides the config
+        #[arg(long, value_name = "NAME")]
+        pub strategy: Option<String>,
+
+        /// Set one of the strategy's own settings, overrides its `[strategies.<name>]` section.
+        /// The value is read as TOML, so `--strategy-param grab_boost=false` sets a bool
+        #[arg(long, value_name = "KEY=VALUE", value_parser = parse_param)]
+        pub strategy_param: Vec<(String, toml::Value)>,
+
+        /// Print the strategies we know about and exit
+        #[arg(long)]
+        pub list_strategies: bool,
+
+        /// Config file to load (defaults to ./bot.toml, if there is one)
+        #[arg(short, long, value_name = "FILE")]
+        pub config: Option<PathBuf>,
+
+        /// Address of the RLBot server, overrides the config
+        #[arg(long)]
+        pub address: Option<String>,
+
+        /// Index of the car to control, overrides the config and the CAR_ID env var
+        #[arg(long)]
+        pub car_id: Option<usize>,
+
+        /// Turn off debug rendering, overrides the config
+        #[arg(long)]
+        pub no_render: bool,
+
+        /// Write every packet sent to and received from RLBot to this file
+        #[arg(short, long, value_name = "FILE")]
+        pub record: Option<PathBuf>,
+
+        /// Replay a match log through the bot offline and report the first tick where its inputs
+        /// differ from the recorded ones
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["start", "record"])]
+        pub replay: Option<PathBuf>,
+    }
+
+    /// Split `key=value`. Anything that isn't valid TOML is taken as a plain string, so
+    /// `--strategy-param mode=fast` works without quoting
+    fn parse_param(arg: &str) -> Result<(String, toml::Value), String> {
+        let (key, value) = arg
+            .split_once('=')
+            .ok_or_else(|| format!("expected KEY=VALUE, got {arg:?}"))?;
+        let value = format!("v = {value}")
+            .parse::<toml::Table>()
+            .ok()
+            .and_then(|mut table| table.remove("v"))
+            .unwrap_or_else(|| toml::Value::String(value.to_owned()));
+        Ok((key.trim().to_owned(), value))
     }
 }
@@ -0,0 +1,132 @@
+/// The bot's configuration file. Everything here has a default, so the file (and every key in
+/// it) is optional. See `bot.toml` at the root of the repo for an example.
+
+pub mod config {
+    use std::{collections::HashMap, fs, path::Path};
+
+    use serde::Deserialize;
+
+    use crate::actions::{goto_action::GotoAction, kickoff_action::BasicKickoffAction};
+
+    /// Where we look for a config file when one isn't passed on the command line
+    pub const DEFAULT_PATH: &str = "bot.toml";
+
+    #[derive(Debug)]
+    pub enum ConfigError {
+        Read(std::io::Error),
+        Parse(toml::de::Error),
+    }
+
+    impl std::fmt::Display for ConfigError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                ConfigError::Read(e) => write!(f, "failed to read config: {e}"),
+                ConfigError::Parse(e) => write!(f, "failed to parse config: {e}"),
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Default, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct Config {
+        pub connection: ConnectionConfig,
+        pub bot: BotConfig,
+        pub tuning: Tuning,
+        /// Each strategy's own settings, from `[strategies.<name>]` sections
+        pub strategies: HashMap<String, toml::Table>,
+    }
+
+    #[derive(Clone, Debug, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct ConnectionConfig {
+        /// Where the RLBot server is listening
+        pub address: String,
+        /// How many times to try (re)connecting before giving up
+        pub reconnect_attempts: u32,
+        /// Delay before the first retry, doubled after every failure
+        pub reconnect_delay_ms: u64,
+        /// The longest we'll wait between retries
+        pub max_reconnect_delay_ms: u64,
+    }
+
+    impl Default for ConnectionConfig {
+        fn default() -> ConnectionConfig {
+            ConnectionConfig {
+                address: String::from("127.0.0.1:23234"),
+                reconnect_attempts: 10,
+                reconnect_delay_ms: 250,
+                max_reconnect_delay_ms: 5000,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct BotConfig {
+        /// Index of the car we control
+        pub car_id: usize,
+        pub debug_rendering: bool,
+        /// Which strategy to play with, see `--list-strategies`
+        pub strategy: String,
+    }
+
+    impl Default for BotConfig {
+        fn default() -> BotConfig {
+            BotConfig {
+                car_id: 0,
+                debug_rendering: true,
+                strategy: String::from("solo"),
+            }
+        }
+    }
+
+    /// Numbers that change how the bot plays
+    #[derive(Clone, Debug, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct Tuning {
+        /// How far GotoAction shifts its target to arrive from the right direction, as a fraction
+        /// of the distance to the target
+        pub goto_lerp_t: f32,
+        /// Speed to reach before the first kickoff flip when we start in the middle
+        pub kickoff_center_speed: f32,
+        /// Speed to reach before the first kickoff flip from any other spawn
+        pub kickoff_offcenter_speed: f32,
+        /// Go for boost when we have less than this much
+        pub boost_grab_threshold: i32,
+        /// Boost we hold on to when attacking or defending, only spent on the way to a pad
+        pub boost_reserve: i32,
+        /// How much more utility a new plan needs before we drop the one we're running
+        pub switch_margin: f32,
+    }
+
+    impl Default for Tuning {
+        fn default() -> Tuning {
+            Tuning {
+                goto_lerp_t: GotoAction::DEFAULT_LERP_T,
+                kickoff_center_speed: BasicKickoffAction::DEFAULT_CENTER_SPEED,
+                kickoff_offcenter_speed: BasicKickoffAction::DEFAULT_OFFCENTER_SPEED,
+                boost_grab_threshold: 30,
+                boost_reserve: 0,
+                switch_margin: 0.15,
+            }
+        }
+    }
+
+    impl Config {
+        pub fn parse(text: &str) -> Result<Config, ConfigError> {
+            toml::from_str(text).map_err(ConfigError::Parse)
+        }
+
+        /// Load the config at `path`. Without a path, fall back to `DEFAULT_PATH` if it exists,
+        /// and to the defaults if it doesn't
+        pub fn load(path: Option<&Path>) -> Result<Config, ConfigError> {
+            let path = match path {
+                Some(path) => path,
+                None if Path::new(DEFAULT_PATH).exists() => Path::new(DEFAULT_PATH),
+                None => return Ok(Config::default()),
+            };
+            let text = fs::read_to_string(path).map_err(ConfigError::Read)?;
+            Config::parse(&text)
+        }
+    }
+}
@@ -1,114 +1,153 @@
 use clap::Parser;
-use rlbot_lib::{
-    self,
-    rlbot::{QuickChat, QuickChatSelection, ReadyMessage, RenderGroup, PredictionSlice},
-    Packet, RLBotConnection,
-};
-use std::env;
+use std::{env, process::ExitCode, time::Duration};
 
 use crate::{
     bot::bot::Agent,
-    strategies::{solo_strategy::SoloStrategy, test_strategy::TestStrategy},
+    config::config::Config,
+    recorder::recorder::{LogReader, Recorder},
+    replay::replay::replay,
+    session::session::{Backoff, Session},
+    strategies::registry::{self, StrategyParams},
 };
 
 mod actions;
 mod bot;
 mod cli;
+mod config;
+mod recorder;
+mod replay;
+mod session;
 mod strategies;
+#[cfg(test)]
+mod test;
 mod utils;
 
-const DEFAULT_CAR_ID: usize = 0;
-
-fn main() -> ! {
-    println!("Connecting");
+// Exit codes, so scripts running a bunch of matches can tell what happened
+/// The match ended normally, or a replay matched the recording
+const EXIT_OK: u8 = 0;
+/// A replay didn't match the recording
+const EXIT_REPLAY_DIVERGED: u8 = 1;
+/// Couldn't reach RLBot, or lost it and couldn't get it back
+const EXIT_CONNECTION: u8 = 2;
+/// Couldn't read or write a match log
+const EXIT_LOG: u8 = 3;
+/// The config file or the command line didn't make sense
+const EXIT_CONFIG: u8 = 4;
 
+fn main() -> ExitCode {
     let args = cli::cli::Args::parse();
-    let mut rlbot_connection = RLBotConnection::new("127.0.0.1:23234").expect("connection");
 
-    if args.start {
-        println!("Starting Match");
-        let match_settings = utils::rl_match::start_match();
-        rlbot_connection
-            .send_packet(Packet::MatchSettings(match_settings))
-            .expect("Failed to Start Match");
+    if args.list_strategies {
+        for entry in registry::STRATEGIES {
+            println!("{:<8} {}", entry.name, entry.description);
+        }
+        return ExitCode::from(EXIT_OK);
     }
 
-    println!("Running!");
-
-    rlbot_connection
-        .send_packet(Packet::ReadyMessage(ReadyMessage {
-            wantsBallPredictions: true,
-            wantsQuickChat: true,
-            wantsGameMessages: true,
-        }))
-        .unwrap();
-
-    let car_id = env::var("CAR_ID")
-        .map(|x| x.parse().unwrap())
-        .unwrap_or(DEFAULT_CAR_ID);
+    let mut config = match Config::load(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("{e}");
+            return ExitCode::from(EXIT_CONFIG);
+        }
+    };
 
-    println!("Creating Agent");
-    let mut agent: Agent;
+    // the command line wins over the environment, which wins over the config file
+    if let Ok(car_id) = env::var("CAR_ID") {
+        match car_id.parse() {
+            Ok(car_id) => config.bot.car_id = car_id,
+            Err(_) => println!("Ignoring CAR_ID, {car_id:?} isn't a car index"),
+        }
+    }
+    if let Some(car_id) = args.car_id {
+        config.bot.car_id = car_id;
+    }
+    if let Some(address) = args.address.as_ref() {
+        config.connection.address = address.clone();
+    }
+    if args.no_render {
+        config.bot.debug_rendering = false;
+    }
     if args.test {
-        agent = Agent::new(true, car_id, TestStrategy {});
-    } else {
-        agent = Agent::new(true, car_id, SoloStrategy {});
+        config.bot.strategy = String::from("test");
+    }
+    if let Some(strategy) = args.strategy.as_ref() {
+        config.bot.strategy = strategy.clone();
     }
 
-    let mut count = 0;
-    let mut latest_predictions: Vec<PredictionSlice> = vec![];
-    loop {
-        match rlbot_connection.recv_packet() {
-            Ok(received_packet) => {
-                match received_packet {
-                    Packet::GameTickPacket(packet) => {
-                        count += 1;
-                        let res = agent.handle_game_tick(packet, &latest_predictions);
-                        // println!("{:?}", res.input);
-                        // println!("{:?}", res.render);
-                        rlbot_connection
-                            .send_packet(Packet::PlayerInput(res.input))
-                            .unwrap();
-                        rlbot_connection
-                            .send_packet(Packet::RenderGroup(RenderGroup {
-                                renderMessages: Some(res.render),
-                                id: 456, // NOTE: ~~I might need to make these unique.~~ I don't
-                            }))
-                            .unwrap();
+    println!("Creating Agent");
+    let name = config.bot.strategy.clone();
+    let mut params = StrategyParams {
+        tuning: config.tuning.clone(),
+        params: config.strategies.remove(&name).unwrap_or_default(),
+    };
+    for (key, value) in args.strategy_param.iter() {
+        params.params.insert(key.clone(), value.clone());
+    }
+    let strategy = match registry::build(&name, &params) {
+        Ok(strategy) => strategy,
+        Err(e) => {
+            println!("Failed to create strategy {name:?}: {e}");
+            return ExitCode::from(EXIT_CONFIG);
+        }
+    };
+    let mut agent = Agent::new(config.bot.debug_rendering, config.bot.car_id, strategy);
 
-                        // launch the car every 4 seconds
-                        if count % (240 * 4) == 0 {
-                            if let Some(state) = agent.strategy.set_game_state() {
-                                rlbot_connection
-                                    .send_packet(Packet::DesiredGameState(state))
-                                    .unwrap();
-                            }
-                        }
-                    }
-                    Packet::QuickChat(packet) => {
-                        if packet.quickChatSelection == QuickChatSelection::Compliments_WhatASave {
-                            // WARN: ignoring a result here
-                            let _ = rlbot_connection.send_packet(Packet::QuickChat(QuickChat {
-                                quickChatSelection: QuickChatSelection::Compliments_Thanks,
-                                ..QuickChat::default()
-                            }));
-                        }
-                    }
-                    Packet::BallPrediction(packet) => {
-                        latest_predictions = packet.slices.unwrap();
-                    }
-                    // Packet::ReadyMessage(_) => todo!(),
-                    // Packet::MessagePacket(packet) => {
-                    //     println!("{packet:?}");
-                    // }
-                    // Packet::FieldInfo(_) => continue,
-                    // Packet::MatchSettings(_) => continue,
-                    // Packet::DesiredGameState(_) => continue,
-                    // Packet::RenderGroup(_) => todo!(),
-                    _ => continue,
-                };
+    if let Some(path) = args.replay.as_ref() {
+        println!("Replaying {}", path.display());
+        let report = match LogReader::open(path).and_then(|log| replay(log, &mut agent)) {
+            Ok(report) => report,
+            Err(e) => {
+                println!("Failed to read match log: {e}");
+                return ExitCode::from(EXIT_LOG);
             }
-            Err(_e) => {} // println!("packet error:\n{e:?}"),
         };
+        report.print();
+        return if report.divergence.is_none() {
+            ExitCode::from(EXIT_OK)
+        } else {
+            ExitCode::from(EXIT_REPLAY_DIVERGED)
+        };
+    }
+
+    println!("Connecting");
+    let backoff = Backoff {
+        attempts: config.connection.reconnect_attempts,
+        initial_delay: Duration::from_millis(config.connection.reconnect_delay_ms),
+        max_delay: Duration::from_millis(config.connection.max_reconnect_delay_ms),
+    };
+    let mut session = match Session::new(&config.connection.address, backoff, agent) {
+        Ok(session) => session,
+        Err(e) => {
+            println!("{e}");
+            return ExitCode::from(EXIT_CONNECTION);
+        }
+    };
+
+    if let Some(path) = args.record.as_ref() {
+        println!("Recording match to {}", path.display());
+        match Recorder::create(path) {
+            Ok(recorder) => session.record_to(recorder),
+            Err(e) => {
+                println!("Failed to create match log: {e}");
+                return ExitCode::from(EXIT_LOG);
+            }
+        }
+    }
+
+    let setup = if args.start {
+        println!("Starting Match");
+        session.start_match().and_then(|_| session.ready())
+    } else {
+        session.ready()
+    };
+
+    println!("Running!");
+    match setup.and_then(|_| session.run()) {
+        Ok(()) => ExitCode::from(EXIT_OK),
+        Err(e) => {
+            println!("Giving up: {e}");
+            ExitCode::from(EXIT_CONNECTION)
+        }
     }
 }
@@ -0,0 +1,167 @@
+/// Recording matches to disk, so a failed maneuver can be debugged after the fact.
+///
+/// A log is a short header followed by a flat list of entries. Each entry is:
+///
+/// | size | field                                         |
+/// |------|-----------------------------------------------|
+/// | 1    | kind (received packet, sent packet, action)   |
+/// | 8    | tick count when the entry was written         |
+/// | 8    | milliseconds since the recording started      |
+/// | 2    | RLBot data type (0 for action entries)        |
+/// | 4    | payload size                                  |
+/// | n    | flatbuffer payload, or the action name (utf8) |
+///
+/// Everything is little endian.
+
+pub mod recorder {
+    use std::{
+        fs::File,
+        io::{self, BufReader, BufWriter, Read, Write},
+        path::Path,
+        time::Instant,
+    };
+
+    use rlbot_lib::Packet;
+
+    use crate::utils::wire::wire::{decode, encode, WireError};
+
+    const MAGIC: &[u8; 4] = b"RLRC";
+    const VERSION: u8 = 1;
+
+    const KIND_RECEIVED: u8 = 0;
+    const KIND_SENT: u8 = 1;
+    const KIND_ACTION: u8 = 2;
+
+    pub enum EntryKind {
+        /// A packet RLBot sent us
+        Received(Packet),
+        /// A packet we sent to RLBot
+        Sent(Packet),
+        /// The name of the action that was in control for this tick
+        Action(String),
+    }
+
+    pub struct Entry {
+        pub tick: u64,
+        pub elapsed_ms: u64,
+        pub kind: EntryKind,
+    }
+
+    pub struct Recorder {
+        writer: BufWriter<File>,
+        start: Instant,
+    }
+
+    impl Recorder {
+        pub fn create(path: &Path) -> io::Result<Recorder> {
+            let mut writer = BufWriter::new(File::create(path)?);
+            writer.write_all(MAGIC)?;
+            writer.write_all(&[VERSION])?;
+            Ok(Recorder {
+                writer,
+                start: Instant::now(),
+            })
+        }
+
+        pub fn received(&mut self, tick: u64, packet: &Packet) -> io::Result<()> {
+            let (data_type, payload) = encode(packet);
+            self.write_entry(KIND_RECEIVED, tick, data_type, &payload)
+        }
+
+        pub fn sent(&mut self, tick: u64, packet: &Packet) -> io::Result<()> {
+            let (data_type, payload) = encode(packet);
+            self.write_entry(KIND_SENT, tick, data_type, &payload)
+        }
+
+        pub fn action(&mut self, tick: u64, name: &str) -> io::Result<()> {
+            self.write_entry(KIND_ACTION, tick, 0, name.as_bytes())
+        }
+
+        pub fn flush(&mut self) -> io::Result<()> {
+            self.writer.flush()
+        }
+
+        fn write_entry(
+            &mut self,
+            kind: u8,
+            tick: u64,
+            data_type: u16,
+            payload: &[u8],
+        ) -> io::Result<()> {
+            let elapsed_ms = self.start.elapsed().as_millis() as u64;
+            self.writer.write_all(&[kind])?;
+            self.writer.write_all(&tick.to_le_bytes())?;
+            self.writer.write_all(&elapsed_ms.to_le_bytes())?;
+            self.writer.write_all(&data_type.to_le_bytes())?;
+            self.writer.write_all(&(payload.len() as u32).to_le_bytes())?;
+            self.writer.write_all(payload)
+        }
+    }
+
+    /// Reads the entries of a log back, in the order they were written
+    pub struct LogReader {
+        reader: BufReader<File>,
+    }
+
+    impl LogReader {
+        pub fn open(path: &Path) -> io::Result<LogReader> {
+            let mut reader = BufReader::new(File::open(path)?);
+            let mut header = [0u8; 5];
+            reader.read_exact(&mut header)?;
+            if &header[..4] != MAGIC || header[4] != VERSION {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "not a match log, or a log from a different version",
+                ));
+            }
+            Ok(LogReader { reader })
+        }
+
+        fn read_entry(&mut self) -> io::Result<Option<Entry>> {
+            let mut kind = [0u8; 1];
+            match self.reader.read_exact(&mut kind) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+                Err(e) => return Err(e),
+            }
+            let mut header = [0u8; 22];
+            self.reader.read_exact(&mut header)?;
+            let tick = u64::from_le_bytes(header[0..8].try_into().unwrap());
+            let elapsed_ms = u64::from_le_bytes(header[8..16].try_into().unwrap());
+            let data_type = u16::from_le_bytes(header[16..18].try_into().unwrap());
+            let size = u32::from_le_bytes(header[18..22].try_into().unwrap()) as usize;
+            let mut payload = vec![0u8; size];
+            self.reader.read_exact(&mut payload)?;
+
+            let invalid =
+                |e: WireError| io::Error::new(io::ErrorKind::InvalidData, format!("{e:?}"));
+            let kind = match kind[0] {
+                KIND_RECEIVED => {
+                    EntryKind::Received(decode(data_type, &payload).map_err(invalid)?)
+                }
+                KIND_SENT => EntryKind::Sent(decode(data_type, &payload).map_err(invalid)?),
+                KIND_ACTION => EntryKind::Action(String::from_utf8_lossy(&payload).into_owned()),
+                other => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unknown entry kind {other}"),
+                    ))
+                }
+            };
+
+            Ok(Some(Entry {
+                tick,
+                elapsed_ms,
+                kind,
+            }))
+        }
+    }
+
+    impl Iterator for LogReader {
+        type Item = io::Result<Entry>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.read_entry().transpose()
+        }
+    }
+}
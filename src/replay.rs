@@ -0,0 +1,122 @@
+/// Replaying a recorded match log through a fresh Agent, to see which decisions changed after
+/// editing an action or strategy. No game needed.
+
+pub mod replay {
+    use std::{collections::HashMap, io};
+
+    use rlbot_lib::{
+        rlbot::{ControllerState, PredictionSlice},
+        Packet,
+    };
+
+    use crate::{
+        bot::bot::Agent,
+        recorder::recorder::{EntryKind, LogReader},
+    };
+
+    /// Controller values closer than this are considered the same
+    const TOLERANCE: f32 = 1e-4;
+
+    /// The first tick where the replayed Agent didn't do what the recorded one did
+    pub struct Divergence {
+        pub tick: u64,
+        pub recorded: ControllerState,
+        pub replayed: ControllerState,
+        pub recorded_action: Option<String>,
+        pub replayed_action: Option<String>,
+    }
+
+    pub struct ReplayReport {
+        /// How many ticks were compared
+        pub ticks: u64,
+        pub divergence: Option<Divergence>,
+    }
+
+    /// Feed every recorded tick into `agent`, and compare what it sends with what was recorded.
+    /// Stops at the first tick that differs
+    pub fn replay(log: LogReader, agent: &mut Agent) -> io::Result<ReplayReport> {
+        let mut latest_predictions: Vec<PredictionSlice> = vec![];
+        // what the replayed agent did on ticks we haven't seen the recorded input for yet
+        let mut replayed: HashMap<u64, (ControllerState, Option<String>)> = HashMap::new();
+        let mut recorded_actions: HashMap<u64, String> = HashMap::new();
+        let mut ticks = 0;
+
+        for entry in log {
+            let entry = entry?;
+            match entry.kind {
+                EntryKind::Received(Packet::BallPrediction(prediction)) => {
+                    latest_predictions = prediction.slices.unwrap_or_default();
+                }
+                EntryKind::Received(Packet::GameTickPacket(packet)) => {
+                    let res = agent.handle_game_tick(packet, &latest_predictions);
+                    let controller = res.input.controllerState.map(|c| *c).unwrap_or_default();
+                    replayed.insert(entry.tick, (controller, res.action));
+                }
+                EntryKind::Action(name) => {
+                    recorded_actions.insert(entry.tick, name);
+                }
+                EntryKind::Sent(Packet::PlayerInput(input)) => {
+                    let (controller, replayed_action) = match replayed.remove(&entry.tick) {
+                        Some(r) => r,
+                        None => continue,
+                    };
+                    ticks += 1;
+                    let recorded = input.controllerState.map(|c| *c).unwrap_or_default();
+                    if !same_controls(&recorded, &controller) {
+                        return Ok(ReplayReport {
+                            ticks,
+                            divergence: Some(Divergence {
+                                tick: entry.tick,
+                                recorded,
+                                replayed: controller,
+                                recorded_action: recorded_actions.remove(&entry.tick),
+                                replayed_action,
+                            }),
+                        });
+                    }
+                    recorded_actions.remove(&entry.tick);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(ReplayReport {
+            ticks,
+            divergence: None,
+        })
+    }
+
+    fn same_controls(a: &ControllerState, b: &ControllerState) -> bool {
+        let close = |x: f32, y: f32| (x - y).abs() < TOLERANCE;
+        close(a.throttle, b.throttle)
+            && close(a.steer, b.steer)
+            && close(a.pitch, b.pitch)
+            && close(a.yaw, b.yaw)
+            && close(a.roll, b.roll)
+            && a.jump == b.jump
+            && a.boost == b.boost
+            && a.handbrake == b.handbrake
+    }
+
+    impl ReplayReport {
+        pub fn print(&self) {
+            match self.divergence.as_ref() {
+                None => println!("Replayed {} ticks, no differences", self.ticks),
+                Some(d) => {
+                    let unknown = String::from("no action");
+                    println!("Replayed {} ticks, first difference at tick {}", self.ticks, d.tick);
+                    println!(
+                        "  recorded ({}): {:?}",
+                        d.recorded_action.as_ref().unwrap_or(&unknown),
+                        d.recorded
+                    );
+                    println!(
+                        "  replayed ({}): {:?}",
+                        d.replayed_action.as_ref().unwrap_or(&unknown),
+                        d.replayed
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+/// The connection loop. Owns the RLBot connection and the Agent, and shuttles packets between
+/// them. Kept separate from main so it can be driven by the mock server in the tests.
+
+pub mod session {
+    use std::{io, thread, time::Duration};
+
+    use rlbot_lib::{
+        rlbot::{PredictionSlice, QuickChat, QuickChatSelection, ReadyMessage, RenderGroup},
+        Packet, RLBotConnection,
+    };
+
+    use crate::{bot::bot::Agent, recorder::recorder::Recorder, utils::rl_match};
+
+    /// After this many failed receives in a row we assume the server is gone. A single bad
+    /// packet shouldn't make us reconnect
+    const MAX_RECV_ERRORS: u32 = 10;
+
+    #[derive(Debug)]
+    pub enum ConnectionError {
+        /// Couldn't open the connection, even after retrying
+        Connect(String),
+        /// A packet failed to send
+        Send(String),
+        /// Too many receives failed in a row, the server probably went away
+        Lost(String),
+    }
+
+    impl std::fmt::Display for ConnectionError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                ConnectionError::Connect(e) => write!(f, "failed to connect to RLBot: {e}"),
+                ConnectionError::Send(e) => write!(f, "failed to send packet: {e}"),
+                ConnectionError::Lost(e) => write!(f, "lost connection to RLBot: {e}"),
+            }
+        }
+    }
+
+    /// How hard to try when (re)connecting. The delay doubles after every failed attempt
+    #[derive(Clone, Debug)]
+    pub struct Backoff {
+        pub attempts: u32,
+        pub initial_delay: Duration,
+        pub max_delay: Duration,
+    }
+
+    impl Default for Backoff {
+        fn default() -> Backoff {
+            Backoff {
+                attempts: 10,
+                initial_delay: Duration::from_millis(250),
+                max_delay: Duration::from_secs(5),
+            }
+        }
+    }
+
+    /// What happened on a step of the loop
+    #[derive(Debug, PartialEq)]
+    pub enum Status {
+        Running,
+        MatchEnded,
+    }
+
+    pub struct Session {
+        addr: String,
+        backoff: Backoff,
+        connection: RLBotConnection,
+        pub agent: Agent,
+        /// number of game ticks we've responded to
+        pub tick_count: u64,
+        latest_predictions: Vec<PredictionSlice>,
+        /// When set, every packet we send or receive is written to the match log
+        recorder: Option<Recorder>,
+        recv_errors: u32,
+        /// Whether we've seen a match in progress. A packet saying the match is over only means
+        /// something after this, otherwise we'd quit on the end screen of the previous match
+        match_started: bool,
+    }
+
+    fn connect(addr: &str, backoff: &Backoff) -> Result<RLBotConnection, ConnectionError> {
+        let mut delay = backoff.initial_delay;
+        let mut last_error = String::new();
+        for attempt in 1..=backoff.attempts.max(1) {
+            match RLBotConnection::new(addr) {
+                Ok(connection) => return Ok(connection),
+                Err(e) => {
+                    last_error = format!("{e:?}");
+                    println!("Connection attempt {attempt} failed: {last_error}");
+                }
+            }
+            if attempt < backoff.attempts {
+                thread::sleep(delay);
+                delay = (delay * 2).min(backoff.max_delay);
+            }
+        }
+        Err(ConnectionError::Connect(last_error))
+    }
+
+    impl Session {
+        pub fn new(addr: &str, backoff: Backoff, agent: Agent) -> Result<Session, ConnectionError> {
+            let connection = connect(addr, &backoff)?;
+            Ok(Session {
+                addr: addr.to_owned(),
+                backoff,
+                connection,
+                agent,
+                tick_count: 0,
+                latest_predictions: vec![],
+                recorder: None,
+                recv_errors: 0,
+                match_started: false,
+            })
+        }
+
+        pub fn record_to(&mut self, recorder: Recorder) {
+            self.recorder = Some(recorder);
+        }
+
+        pub fn start_match(&mut self) -> Result<(), ConnectionError> {
+            let match_settings = rl_match::start_match();
+            self.send(Packet::MatchSettings(match_settings))
+        }
+
+        /// Tell RLBot which packets we want to receive
+        pub fn ready(&mut self) -> Result<(), ConnectionError> {
+            self.send(Packet::ReadyMessage(ReadyMessage {
+                wantsBallPredictions: true,
+                wantsQuickChat: true,
+                wantsGameMessages: true,
+            }))
+        }
+
+        /// Play until the match ends, reconnecting whenever the connection drops. Only returns an
+        /// error once we've given up on reconnecting
+        pub fn run(&mut self) -> Result<(), ConnectionError> {
+            loop {
+                match self.step() {
+                    Ok(Status::MatchEnded) => return Ok(()),
+                    Ok(Status::Running) => {}
+                    Err(e) => {
+                        println!("{e}, reconnecting");
+                        self.reconnect()?;
+                    }
+                }
+            }
+        }
+
+        fn reconnect(&mut self) -> Result<(), ConnectionError> {
+            self.connection = connect(&self.addr, &self.backoff)?;
+            self.recv_errors = 0;
+            self.ready()
+        }
+
+        /// Wait for the next packet from RLBot and respond to it
+        pub fn step(&mut self) -> Result<Status, ConnectionError> {
+            match self.connection.recv_packet() {
+                Ok(received_packet) => {
+                    self.recv_errors = 0;
+                    if let Packet::GameTickPacket(_) = received_packet {
+                        self.tick_count += 1;
+                    }
+                    let tick = self.tick_count;
+                    self.record(|recorder| recorder.received(tick, &received_packet));
+                    self.handle_packet(received_packet)
+                }
+                Err(e) => {
+                    self.record(|recorder| recorder.flush());
+                    self.recv_errors += 1;
+                    if self.recv_errors >= MAX_RECV_ERRORS {
+                        return Err(ConnectionError::Lost(format!("{e:?}")));
+                    }
+                    Ok(Status::Running)
+                }
+            }
+        }
+
+        /// Send a packet to RLBot, logging it first if we're recording
+        fn send(&mut self, packet: Packet) -> Result<(), ConnectionError> {
+            let tick = self.tick_count;
+            self.record(|recorder| recorder.sent(tick, &packet));
+            self.connection
+                .send_packet(packet)
+                .map(|_| ())
+                .map_err(|e| ConnectionError::Send(format!("{e:?}")))
+        }
+
+        /// Run a write against the recorder. A broken recorder shouldn't take the bot down with
+        /// it, so on failure we complain and stop recording
+        fn record(&mut self, write: impl FnOnce(&mut Recorder) -> io::Result<()>) {
+            if let Some(recorder) = self.recorder.as_mut() {
+                if let Err(e) = write(recorder) {
+                    println!("Failed to write match log, recording stopped: {e}");
+                    self.recorder = None;
+                }
+            }
+        }
+
+        fn handle_packet(&mut self, received_packet: Packet) -> Result<Status, ConnectionError> {
+            match received_packet {
+                Packet::GameTickPacket(packet) => {
+                    if let Some(game_info) = packet.gameInfo.as_ref() {
+                        if game_info.isMatchEnded && self.match_started {
+                            println!("Match over");
+                            return Ok(Status::MatchEnded);
+                        }
+                        self.match_started |= game_info.isRoundActive;
+                    }
+
+                    let res = self
+                        .agent
+                        .handle_game_tick(packet, &self.latest_predictions);
+                    // println!("{:?}", res.input);
+                    // println!("{:?}", res.render);
+                    if let Some(name) = res.action.as_ref() {
+                        let tick = self.tick_count;
+                        self.record(|recorder| recorder.action(tick, name));
+                    }
+                    self.send(Packet::PlayerInput(res.input))?;
+                    self.send(Packet::RenderGroup(RenderGroup {
+                        renderMessages: Some(res.render),
+                        id: 456, // NOTE: ~~I might need to make these unique.~~ I don't
+                    }))?;
+                    if let Some(state) = res.game_state {
+                        self.send(Packet::DesiredGameState(state))?;
+                    }
+                    // get the whole tick onto disk, so a crash doesn't cost us the end of the log
+                    self.record(|recorder| recorder.flush());
+                }
+                Packet::QuickChat(packet) => {
+                    if packet.quickChatSelection == QuickChatSelection::Compliments_WhatASave {
+                        // WARN: ignoring a result here
+                        let _ = self.send(Packet::QuickChat(QuickChat {
+                            quickChatSelection: QuickChatSelection::Compliments_Thanks,
+                            ..QuickChat::default()
+                        }));
+                    }
+                }
+                Packet::BallPrediction(packet) => {
+                    self.latest_predictions = packet.slices.unwrap_or_default();
+                }
+                // Packet::ReadyMessage(_) => todo!(),
+                // Packet::MessagePacket(packet) => {
+                //     println!("{packet:?}");
+                // }
+                // Packet::FieldInfo(_) => continue,
+                // Packet::MatchSettings(_) => continue,
+                // Packet::DesiredGameState(_) => continue,
+                // Packet::RenderGroup(_) => todo!(),
+                _ => {}
+            }
+            Ok(Status::Running)
+        }
+    }
+}
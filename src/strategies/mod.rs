@@ -1,19 +1,182 @@
+pub mod script_strategy;
 pub mod solo_strategy;
 pub mod test_strategy;
 
 pub mod strategy {
-    use rlbot_lib::rlbot::{GameTickPacket, DesiredGameState, PredictionSlice};
+    use rlbot_lib::rlbot::{DesiredGameState, PredictionSlice};
 
-    use crate::actions::action::Action;
+    use crate::{
+        actions::action::{Action, FailureReason},
+        utils::world::world::World,
+    };
+
+    /// Why an attempt at a drill ended
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum ResetTrigger {
+        ActionSucceeded,
+        ActionFailed(FailureReason),
+        Goal,
+        BallStopped,
+        Timeout,
+    }
+
+    /// When the agent should put the game back into the strategy's starting position. Everything
+    /// is off by default, which is what you want when playing a real match
+    #[derive(Clone, Debug, Default)]
+    pub struct ResetPolicy {
+        pub on_success: bool,
+        pub on_failure: bool,
+        pub on_goal: bool,
+        /// Reset once the ball has moved and then sat still for a second
+        pub on_ball_stopped: bool,
+        /// Reset after this many seconds, whatever is happening
+        pub timeout: Option<f32>,
+    }
+
+    /// How the previous attempt went
+    #[derive(Clone, Debug)]
+    pub struct Attempt {
+        /// Counts up from 0
+        pub number: u32,
+        pub trigger: ResetTrigger,
+        /// Seconds from the start of the attempt to the reset
+        pub duration: f32,
+        /// Name of the last action the agent ran
+        pub action: Option<String>,
+    }
+
+    /// An action the strategy would like to run, and how much it wants to, roughly from 0 to 1
+    pub struct Candidate {
+        /// What kind of plan this is, like "attack" or "boost". The agent scores the running
+        /// action with this tick's candidate of the same label
+        pub label: &'static str,
+        pub utility: f32,
+        pub action: Box<dyn Action>,
+    }
+
+    /// The candidate with the highest utility
+    pub fn best(candidates: Vec<Candidate>) -> Option<Candidate> {
+        candidates.into_iter().max_by(|a, b| {
+            a.utility
+                .partial_cmp(&b.utility)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+    }
 
     pub trait Strategy {
         fn choose_action(
             &self,
-            tick_packet: GameTickPacket,
+            world: &World,
+            car_id: usize,
             ball_predictions: &Vec<PredictionSlice>,
             kickoff: bool,
         ) -> Option<Box<dyn Action>>;
 
-        fn set_game_state(&self) -> Option<DesiredGameState>;
+        /// Everything worth doing right now, scored. Called every tick: the agent switches to the
+        /// best candidate when it beats the running action by `switch_margin`. Strategies that
+        /// don't score anything only get `choose_action` calls, when nothing is running.
+        /// `current` is the running action: fork it with `clone_box` to step a copy forward and
+        /// see where it goes
+        fn candidates(
+            &self,
+            _world: &World,
+            _car_id: usize,
+            _ball_predictions: &Vec<PredictionSlice>,
+            _kickoff: bool,
+            _current: Option<&dyn Action>,
+        ) -> Vec<Candidate> {
+            vec![]
+        }
+
+        /// How much better a candidate has to be to interrupt the running action, so we don't
+        /// flip between two plans that score about the same
+        fn switch_margin(&self) -> f32 {
+            0.1
+        }
+
+        /// Called whenever an action ends, with why it ended. Failures include actions the agent
+        /// dropped early, as `FailureReason::Preempted`
+        fn on_action_finished(&mut self, _action: &dyn Action, _result: Result<(), FailureReason>) {
+        }
+
+        /// When to call `set_game_state`. Defaults to never
+        fn reset_policy(&self) -> ResetPolicy {
+            ResetPolicy::default()
+        }
+
+        /// Set up the next attempt, after the previous one ended for one of the reasons in
+        /// `reset_policy`
+        fn set_game_state(&self, car_id: usize, previous: &Attempt) -> Option<DesiredGameState>;
+    }
+}
+
+/// Every strategy the bot can play with, looked up by name from the config or the CLI. To add a
+/// strategy, write it in its own file and add an entry to `STRATEGIES`.
+pub mod registry {
+    use serde::de::DeserializeOwned;
+
+    use crate::config::config::Tuning;
+
+    use super::{
+        script_strategy::ScriptStrategy, solo_strategy::SoloStrategy, strategy::Strategy,
+        test_strategy::TestStrategy,
+    };
+
+    /// What a strategy gets to build itself with
+    pub struct StrategyParams {
+        pub tuning: Tuning,
+        /// The strategy's own settings, from the config file and `--strategy-param`
+        pub params: toml::Table,
+    }
+
+    impl StrategyParams {
+        /// Deserialize the strategy's own settings into its params struct
+        pub fn parse<T: DeserializeOwned>(&self) -> Result<T, String> {
+            toml::Value::Table(self.params.clone())
+                .try_into()
+                .map_err(|e| format!("{e}"))
+        }
+    }
+
+    pub struct StrategyEntry {
+        pub name: &'static str,
+        pub description: &'static str,
+        pub build: fn(&StrategyParams) -> Result<Box<dyn Strategy>, String>,
+    }
+
+    pub const STRATEGIES: &[StrategyEntry] = &[
+        StrategyEntry {
+            name: "solo",
+            description: "Plays a 1v1: kickoffs, intercepting the ball, clearing and grabbing boost",
+            build: |params| Ok(Box::new(SoloStrategy::from_params(params)?)),
+        },
+        StrategyEntry {
+            name: "test",
+            description: "Launches the car somewhere random and practices recoveries",
+            build: |params| Ok(Box::new(TestStrategy::from_params(params)?)),
+        },
+        StrategyEntry {
+            name: "script",
+            description: "Runs a maneuver script from a RON file, see `scripts/`",
+            build: |params| Ok(Box::new(ScriptStrategy::from_params(params)?)),
+        },
+    ];
+
+    pub fn find(name: &str) -> Option<&'static StrategyEntry> {
+        STRATEGIES.iter().find(|entry| entry.name == name)
+    }
+
+    pub fn build(name: &str, params: &StrategyParams) -> Result<Box<dyn Strategy>, String> {
+        match find(name) {
+            Some(entry) => (entry.build)(params),
+            None => Err(format!(
+                "no strategy called {name:?}, try one of: {}",
+                STRATEGIES
+                    .iter()
+                    .map(|entry| entry.name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
     }
 }
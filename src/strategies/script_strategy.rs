@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use rlbot_lib::rlbot::{DesiredGameState, PredictionSlice};
+use serde::Deserialize;
+
+use crate::{
+    actions::{
+        action::Action,
+        script_action::{Script, ScriptAction},
+    },
+    utils::world::world::World,
+};
+
+use super::{
+    registry::StrategyParams,
+    strategy::{Attempt, Strategy},
+};
+
+/// Runs a maneuver script over and over, for trying out a maneuver without writing any code
+pub struct ScriptStrategy {
+    pub script: Script,
+    pub params: ScriptParams,
+}
+
+/// Settings under `[strategies.script]`
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScriptParams {
+    /// The RON file to run. Without one we run the kickoff in `scripts/kickoff_dodge.ron`
+    pub path: Option<String>,
+    /// Only run the script on kickoffs, and sit still the rest of the time
+    pub kickoff_only: bool,
+}
+
+impl ScriptStrategy {
+    pub const DEFAULT_SCRIPT: &'static str = include_str!("../../scripts/kickoff_dodge.ron");
+
+    pub fn from_params(params: &StrategyParams) -> Result<ScriptStrategy, String> {
+        let params: ScriptParams = params.parse()?;
+        let script = match &params.path {
+            Some(path) => Script::load(Path::new(path)),
+            None => Script::parse(ScriptStrategy::DEFAULT_SCRIPT),
+        }
+        .map_err(|e| format!("{e}"))?;
+        Ok(ScriptStrategy { script, params })
+    }
+}
+
+impl Strategy for ScriptStrategy {
+    fn choose_action(
+        &self,
+        _world: &World,
+        car_id: usize,
+        _ball_predictions: &Vec<PredictionSlice>,
+        kickoff: bool,
+    ) -> Option<Box<dyn Action>> {
+        if self.params.kickoff_only && !kickoff {
+            return None;
+        }
+        Some(Box::new(ScriptAction::new(&self.script, car_id)))
+    }
+
+    fn set_game_state(&self, _car_id: usize, _previous: &Attempt) -> Option<DesiredGameState> {
+        None
+    }
+}
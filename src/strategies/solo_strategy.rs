@@ -1,64 +1,154 @@
-use rlbot_lib::rlbot::{DesiredGameState, GameTickPacket, PlayerInfo, PredictionSlice};
+use rlbot_lib::rlbot::{DesiredGameState, PredictionSlice, Vector3};
+use serde::Deserialize;
 
 use crate::{
-    actions::{action::Action, drive_action::DriveAction, kickoff_action::BasicKickoffAction},
+    actions::{
+        action::{Action, FailureReason},
+        behavior_tree::{Blend, Sequence},
+        boost_layer::BoostLayer,
+        dribble_action::DribbleAction,
+        drive_action::DriveAction,
+        half_flip_action::HalfFlipAction,
+        kickoff_action::BasicKickoffAction,
+        strike::StrikeAction,
+    },
+    config::config::Tuning,
     utils::{
         arena::Arena,
         boost::pads::{choose_boostpad, BoostPad},
         intercept::Intercept,
         math::math::Vec3,
+        world::world::{Car, World},
     },
-    DEFAULT_CAR_ID,
 };
 
-use super::strategy::Strategy;
+use super::{
+    registry::StrategyParams,
+    strategy::{best, Attempt, Candidate, Strategy},
+};
+
+#[derive(Default)]
+pub struct SoloStrategy {
+    pub tuning: Tuning,
+    pub params: SoloParams,
+    /// Set once a kickoff's speed flip didn't speed us up, so the later kickoffs just dodge at
+    /// the ball instead
+    speed_flip_failed: bool,
+}
+
+/// Settings under `[strategies.solo]`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SoloParams {
+    /// Go grab boost when we're low and the ball isn't dangerous
+    pub grab_boost: bool,
+    /// Carry the ball on the roof when it's slow and nobody's near it
+    pub dribble: bool,
+}
+
+impl Default for SoloParams {
+    fn default() -> SoloParams {
+        SoloParams {
+            grab_boost: true,
+            dribble: true,
+        }
+    }
+}
+
+impl SoloStrategy {
+    pub fn from_params(params: &StrategyParams) -> Result<SoloStrategy, String> {
+        Ok(SoloStrategy {
+            tuning: params.tuning.clone(),
+            params: params.parse()?,
+            speed_flip_failed: false,
+        })
+    }
 
-pub struct SoloStrategy {}
+    /// Drive somewhere while holding on to our boost reserve
+    fn drive(&self, drive: Box<dyn Action>, car_id: usize) -> Box<dyn Action> {
+        let boost = BoostLayer::new(car_id, self.tuning.boost_reserve);
+        Box::new(Blend::new(drive).layer(1, Box::new(boost)))
+    }
+
+    /// The soonest strike on the ball, aimed at `target`. When the ball's behind us and too far
+    /// to back up to, we half flip around first
+    fn strike(
+        &self,
+        world: &World,
+        car_id: usize,
+        ball_predictions: &Vec<PredictionSlice>,
+        target: &Vector3,
+    ) -> Option<Box<dyn Action>> {
+        let car = world.car(car_id)?;
+        let strike = StrikeAction::choose(car_id, world, ball_predictions, Some(target.clone()))?
+            .with_lerp_t(self.tuning.goto_lerp_t);
+        let behind = strike
+            .intercept
+            .as_ref()
+            .map_or(false, |incpt| HalfFlipAction::worth_it(car, &incpt.location));
+        if behind {
+            Some(Box::new(Sequence::new(vec![
+                Box::new(HalfFlipAction::new(car_id)),
+                Box::new(strike),
+            ])))
+        } else {
+            Some(Box::new(strike))
+        }
+    }
+}
 
 impl Strategy for SoloStrategy {
-    // TODO: GameTickPacket has the ball info in it... I should write a function that parses
-    // that into a struct... or something.
     fn choose_action(
         &self,
-        tick_packet: GameTickPacket,
+        world: &World,
+        car_id: usize,
         ball_predictions: &Vec<PredictionSlice>,
         kickoff: bool,
     ) -> Option<Box<dyn Action>> {
-        let ball = tick_packet.clone().ball.unwrap();
-        let ball_phys = ball.physics.clone().unwrap();
-        let game_time = tick_packet.clone().gameInfo.unwrap().secondsElapsed;
-        let players = tick_packet.players.clone().unwrap();
-        let car = players.get(DEFAULT_CAR_ID).unwrap();
-        let car_phys = car.physics.clone().unwrap();
-        let car_location = car_phys.location.clone().unwrap();
-        let mut opponents = players.clone();
-        opponents.remove(DEFAULT_CAR_ID);
+        best(self.candidates(world, car_id, ball_predictions, kickoff, None))
+            .map(|candidate| candidate.action)
+    }
+
+    fn candidates(
+        &self,
+        world: &World,
+        car_id: usize,
+        ball_predictions: &Vec<PredictionSlice>,
+        kickoff: bool,
+        _current: Option<&dyn Action>,
+    ) -> Vec<Candidate> {
+        let game_time = world.game.seconds_elapsed;
+        let car = match world.car(car_id) {
+            Some(car) => car,
+            None => return vec![],
+        };
+        let car_location = car.location.clone();
 
         let my_goal = Arena::home_goal_pos(car.team);
         let their_goal = Arena::enemy_goal_pos(car.team);
 
         // For now, we're just always driving at the ball.
-        let ball_location = ball.physics.unwrap().location.unwrap();
+        let ball_location = world.ball.location.clone();
 
         let my_intercept = Intercept::new(
             car,
             game_time,
             ball_predictions,
-            *ball_phys.clone(),
-            false,
+            &world.ball,
             false,
+            None,
         );
 
-        let their_intercept = opponents
-            .into_iter()
+        let their_intercept = world
+            .opponents(car.team)
             .map(|opp_car| {
                 Intercept::new(
-                    &opp_car,
+                    opp_car,
                     game_time,
                     ball_predictions,
-                    *ball_phys.clone(),
-                    false,
+                    &world.ball,
                     false,
+                    None,
                 )
             })
             .min_by(|a, b| {
@@ -68,14 +158,17 @@ impl Strategy for SoloStrategy {
             });
 
         // we might have no opponent if they're demoed or leave the game, don't want to crash
-        let mut _opponent: Option<PlayerInfo> = None;
+        let mut _opponent: Option<Car> = None;
+        let mut beat_them_there = true;
         if let Some(op) = their_intercept {
+            beat_them_there = my_intercept.time < op.time;
             _opponent = Some(op.car);
         }
 
-        let pads = BoostPad::extract_info(&tick_packet.clone());
-        let bad_pads: Vec<BoostPad> = pads
-            .into_iter()
+        let bad_pads: Vec<BoostPad> = world
+            .boost_pads
+            .iter()
+            .cloned()
             .filter(|pad| {
                 ((pad.location.y - their_goal.y).abs()
                     < (my_intercept.location.y - their_goal.y).abs()
@@ -83,21 +176,53 @@ impl Strategy for SoloStrategy {
                     && car_location.ground().dist(&pad.location.ground()) < 4000.
             })
             .collect();
-        let best_boost = choose_boostpad(&tick_packet.clone(), car.clone(), &my_goal, bad_pads);
+        let best_boost = choose_boostpad(world, car, &my_goal, bad_pads);
 
-        let mut action: Box<dyn Action>;
         if kickoff {
-            return Some(Box::new(BasicKickoffAction::new()));
-        } else if my_intercept.is_viable {
-            // default drive to intercept the ball
-            action = Box::new(DriveAction::new(
-                my_intercept.location.clone(),
-                2300.,
-                false,
-                false,
-            ))
+            let mut kickoff = BasicKickoffAction::new(car_id);
+            kickoff.center_speed = self.tuning.kickoff_center_speed;
+            kickoff.offcenter_speed = self.tuning.kickoff_offcenter_speed;
+            kickoff.speed_flip = !self.speed_flip_failed;
+            return vec![Candidate {
+                label: "kickoff",
+                utility: 1.,
+                action: Box::new(kickoff),
+            }];
+        }
+
+        let strike = self.strike(world, car_id, ball_predictions, &their_goal);
+
+        let mut candidates = vec![];
+        if let Some(strike) = strike.clone() {
+            // default hit the ball at their goal, more worth it when we get there first
+            candidates.push(Candidate {
+                label: "attack",
+                utility: if beat_them_there { 0.6 } else { 0.5 },
+                action: self.drive(strike, car_id),
+            });
         } else {
-            action = Box::new(DriveAction::new(ball_location.clone(), 2300., false, true))
+            // nothing to hit yet, likely it's up a wall, so follow it there
+            candidates.push(Candidate {
+                label: "attack",
+                utility: 0.4,
+                action: self.drive(
+                    Box::new(DriveAction::new(car_id, ball_location.clone(), 2300., true, true)),
+                    car_id,
+                ),
+            });
+        }
+
+        // nobody's around to take it off us, so keep hold of it on the way to their goal. Only
+        // better than a shot once it's actually on the roof
+        if self.params.dribble && DribbleAction::worth_it(world, car) {
+            candidates.push(Candidate {
+                label: "dribble",
+                utility: if DribbleAction::on_car(car, &world.ball) { 0.7 } else { 0.45 },
+                action: Box::new(
+                    DribbleAction::new(car_id, their_goal.clone())
+                        .with_lerp_t(self.tuning.goto_lerp_t),
+                ),
+            });
         }
 
         // if ball is close to our net, clear it
@@ -107,28 +232,60 @@ impl Strategy for SoloStrategy {
         {
             // clear the ball (with a shot currently, this can be improved by determining the best
             // spot to clear based on the opponent location, I just ran out of time).
-            if car_location.dist(&my_goal) > 2000. {
+            let drive: Box<dyn Action> = if car_location.dist(&my_goal) > 2000. {
                 // TODO: change this to goto with an angle towards the ball, maybe also goto
                 // backpost or something.
-                action = Box::new(DriveAction::new(my_goal.clone(), 2300., false, true));
+                let mut retreat = DriveAction::new(car_id, my_goal.clone(), 2300., false, true);
+                // it's a long way back, worth hopping for speed when we're out of boost
+                retreat.allow_wavedash = true;
+                Box::new(retreat)
+            } else if let Some(strike) = strike {
+                strike
             } else {
-                // NOTE: drive shot action is very broken, needs a bunch of debugging/testing.
-                // I wrote it in one shot without testing b/c i was running out of time.
-                // action = Box::new(DriveShotAction::new(DEFAULT_CAR_ID, their_goal.clone()));
-                action = Box::new(DriveAction::new(ball_location.clone(), 2300., false, false));
-            }
+                Box::new(DriveAction::new(car_id, ball_location.clone(), 2300., false, false))
+            };
+            candidates.push(Candidate {
+                label: "defend",
+                utility: 0.8,
+                action: self.drive(drive, car_id),
+            });
         }
         // low and boost and ball isn't dangerous, so grab boost
-        if let Some(boost_target) = best_boost {
-            if car.boost < 30 && my_intercept.location.ground_dist(&their_goal) > 3000. {
-                action = Box::new(DriveAction::new(boost_target.location, 2300., false, false))
+        if let Some(boost_target) = best_boost.filter(|_| self.params.grab_boost) {
+            if car.boost < self.tuning.boost_grab_threshold
+                && my_intercept.location.ground_dist(&their_goal) > 3000.
+            {
+                candidates.push(Candidate {
+                    label: "boost",
+                    utility: 0.9,
+                    action: Box::new(DriveAction::new(
+                        car_id,
+                        boost_target.location,
+                        2300.,
+                        false,
+                        false,
+                    )),
+                });
             }
         }
 
-        Some(action)
+        candidates
+    }
+
+    fn switch_margin(&self) -> f32 {
+        self.tuning.switch_margin
+    }
+
+    fn on_action_finished(&mut self, action: &dyn Action, _result: Result<(), FailureReason>) {
+        let kickoff = action.as_any().downcast_ref::<BasicKickoffAction>();
+        if let Some(gain) = kickoff.and_then(|kickoff| kickoff.speed_flip_gain()) {
+            if gain <= 0. {
+                self.speed_flip_failed = true;
+            }
+        }
     }
 
-    fn set_game_state(&self) -> Option<DesiredGameState> {
+    fn set_game_state(&self, _car_id: usize, _previous: &Attempt) -> Option<DesiredGameState> {
         None
     }
 }
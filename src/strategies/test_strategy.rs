@@ -1,33 +1,74 @@
 use std::f32::consts::PI;
 
 use rlbot_lib::rlbot::{
-    DesiredCarState, DesiredGameState, DesiredPhysics, Float, GameTickPacket, RotatorPartial, PredictionSlice,
+    DesiredCarState, DesiredGameState, DesiredPhysics, Float, RotatorPartial, PredictionSlice,
 };
 
 use rlbot_lib::rlbot::Vector3Partial;
 
 use crate::{
     actions::{action::Action, recover_action::RecoverAction},
-    utils::arena::Arena,
+    utils::{arena::Arena, world::world::World},
 };
 use rand::Rng;
+use serde::Deserialize;
 
-use super::strategy::Strategy;
+use super::{
+    registry::StrategyParams,
+    strategy::{Attempt, ResetPolicy, Strategy},
+};
+
+#[derive(Default)]
+pub struct TestStrategy {
+    pub params: TestParams,
+}
 
-pub struct TestStrategy {}
+/// Settings under `[strategies.test]`
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TestParams {
+    /// Passed on to the RecoverAction we're testing
+    pub jump_when_upside_down: bool,
+}
+
+impl TestStrategy {
+    pub fn from_params(params: &StrategyParams) -> Result<TestStrategy, String> {
+        Ok(TestStrategy {
+            params: params.parse()?,
+        })
+    }
+}
 
 impl Strategy for TestStrategy {
     fn choose_action(
         &self,
-        _tick_packet: GameTickPacket,
+        _world: &World,
+        car_id: usize,
         _ball_predictions: &Vec<PredictionSlice>,
         _kickoff: bool,
     ) -> Option<Box<dyn Action>> {
-        // let on_ground = tick_packet.players.unwrap().get(DEFAULT_CAR_ID).unwrap().hasWheelContact;
-        Some(Box::new(RecoverAction::new(false)))
+        // let on_ground = world.car(car_id).map(|car| car.has_wheel_contact);
+        Some(Box::new(RecoverAction::new(
+            car_id,
+            self.params.jump_when_upside_down,
+        )))
+    }
+
+    /// Launch the car again as soon as it lands (or gives up), or every 4 seconds if it's stuck
+    fn reset_policy(&self) -> ResetPolicy {
+        ResetPolicy {
+            on_success: true,
+            on_failure: true,
+            timeout: Some(4.),
+            ..Default::default()
+        }
     }
 
-    fn set_game_state(&self) -> Option<DesiredGameState> {
+    fn set_game_state(&self, car_id: usize, previous: &Attempt) -> Option<DesiredGameState> {
+        println!(
+            "Attempt {} ended with {:?} after {:.2}s",
+            previous.number, previous.trigger, previous.duration
+        );
         let mut rng = rand::thread_rng();
         // Pick a random position, rotation, and velocity to give to the car
         let position = Arena::random_pos(300.);
@@ -75,8 +116,12 @@ impl Strategy for TestStrategy {
 
         println!("Car state: {car_state:?}");
 
+        // car states are matched to cars by index, so leave every car before ours untouched
+        let mut car_states = vec![DesiredCarState::default(); car_id];
+        car_states.push(car_state);
+
         Some(DesiredGameState {
-            carStates: Some(vec![car_state]),
+            carStates: Some(car_states),
             ..Default::default()
         })
     }
@@ -0,0 +1,575 @@
+use std::f32::consts::PI;
+
+use rlbot_lib::rlbot::{ControllerState, GameTickPacket};
+
+use crate::{
+    actions::{
+        action::{Action, FailureReason},
+        aerial_action::AerialAction,
+        airdodge_action::AirDodgeAction,
+        behavior_tree::Blend,
+        boost_layer::BoostLayer,
+        drive_action::DriveAction,
+        goto_action::GotoAction,
+        half_flip_action::HalfFlipAction,
+        jump_action::JumpAction,
+        kickoff_action::BasicKickoffAction,
+        recover_action::RecoverAction,
+        reorient_action::ReorientAction,
+        speed_flip_action::SpeedFlipAction,
+        strike::{StrikeAction, StrikeKind},
+        wavedash_action::WavedashAction,
+    },
+    strategies::{solo_strategy::SoloStrategy, strategy::Strategy},
+    utils::{
+        intercept::Intercept,
+        math::math::{vec_new, Vec3},
+        world::world::Car,
+    },
+};
+
+use super::{
+    builder::{CarBuilder, PacketBuilder, PredictionBuilder},
+    harness::ActionHarness,
+};
+
+fn harness(car: CarBuilder) -> ActionHarness {
+    ActionHarness::new(PacketBuilder::new().car(car).build())
+}
+
+/// How many times the jump button goes down
+fn jump_presses(controllers: &Vec<ControllerState>) -> usize {
+    let mut last = false;
+    controllers
+        .iter()
+        .filter(|c| {
+            let pressed = c.jump && !last;
+            last = c.jump;
+            pressed
+        })
+        .count()
+}
+
+#[test]
+fn jump_holds_for_its_duration_then_lets_go() {
+    let run = harness(CarBuilder::new()).run(&mut JumpAction::new(0.205), 100);
+    assert!(run.succeeded());
+    assert_eq!(run.ticks_while(|c| c.jump), 25);
+    // one tick off the button before it's done
+    assert_eq!(run.controllers.len(), 26);
+}
+
+#[test]
+fn drive_boosts_straight_at_a_target_ahead() {
+    let mut drive = DriveAction::new(0, vec_new(2000., -2000., 17.), 2300., false, false);
+    let run = harness(CarBuilder::new()).run(&mut drive, 10);
+    assert!(run.finished.is_none());
+    run.assert_all("full throttle and boost", |c| c.throttle == 1. && c.boost);
+    run.assert_all("no steering", |c| c.steer.abs() < 1e-3);
+    assert!(drive.expected_duration().unwrap() > 1.);
+    assert_eq!(drive.progress(), Some(2000.));
+}
+
+#[test]
+fn drive_turns_hard_towards_a_target_to_the_side() {
+    // facing +x, so +y is on our right
+    let mut drive = DriveAction::new(0, vec_new(0., 0., 17.), 2300., false, false);
+    let run = harness(CarBuilder::new()).run(&mut drive, 1);
+    assert_eq!(run.tick(0).steer, 1.);
+    assert!(run.tick(0).handbrake);
+    // no boosting while we're facing the wrong way
+    assert!(!run.tick(0).boost);
+
+    let mut drive = DriveAction::new(0, vec_new(0., -4000., 17.), 2300., false, false);
+    let run = harness(CarBuilder::new()).run(&mut drive, 1);
+    assert_eq!(run.tick(0).steer, -1.);
+}
+
+#[test]
+fn drive_succeeds_on_arrival_and_fails_when_getting_further_away() {
+    let mut drive = DriveAction::new(0, vec_new(100., -2000., 17.), 1000., false, false);
+    assert!(harness(CarBuilder::new()).run(&mut drive, 1).succeeded());
+
+    let mut drive = DriveAction::new(0, vec_new(2000., -2000., 17.), 1000., false, false);
+    let run = harness(CarBuilder::new()).run_with(&mut drive, 10, |packet, tick, _| {
+        let physics = packet.players.as_mut().unwrap()[0].physics.as_mut().unwrap();
+        physics.location = Some(vec_new(-300. * tick as f32, -2000., 17.));
+    });
+    assert!(run.failed_with(FailureReason::TargetUnreachable));
+}
+
+#[test]
+fn drive_backs_up_to_a_target_just_behind() {
+    let mut drive = DriveAction::new(0, vec_new(-600., -2000., 17.), 1000., false, false);
+    let run = harness(CarBuilder::new()).run(&mut drive, 5);
+    run.assert_all("reversing without boost", |c| c.throttle == -1. && !c.boost);
+    run.assert_all("no steering", |c| c.steer.abs() < 1e-3);
+    assert_eq!(drive.name(), "DriveAction (reverse)");
+
+    // behind us and off to the left, so the back has to swing left
+    let mut drive = DriveAction::new(0, vec_new(-600., -2150., 17.), 1000., false, false);
+    let run = harness(CarBuilder::new()).run(&mut drive, 1);
+    assert!(run.tick(0).steer < 0.);
+
+    // too far to be worth backing up
+    let mut drive = DriveAction::new(0, vec_new(-3000., -2000., 17.), 1000., false, false);
+    let run = harness(CarBuilder::new()).run(&mut drive, 1);
+    assert_eq!(run.tick(0).throttle, 1.);
+}
+
+#[test]
+fn drive_only_counts_progress_once_facing_the_target() {
+    // too far behind to back up, so we have to turn around, drifting away while we do
+    let mut drive = DriveAction::new(0, vec_new(-3000., -2000., 17.), 2300., false, false);
+    let run = harness(CarBuilder::new()).run_with(&mut drive, 150, |packet, tick, _| {
+        let physics = packet.players.as_mut().unwrap()[0].physics.as_mut().unwrap();
+        physics.location = Some(vec_new(2. * tick as f32, -2000., 17.));
+    });
+    assert!(run.finished.is_none());
+    assert_eq!(drive.progress(), None);
+
+    let run = harness(CarBuilder::new().facing(PI)).run(&mut drive, 1);
+    assert!(run.finished.is_none());
+    assert_eq!(drive.progress(), Some(3000.));
+}
+
+#[test]
+fn drive_heads_for_the_seam_under_a_wall_target() {
+    // the wall is on our left, and the target's high enough up it that the way there crosses
+    // the seam well before the spot under it
+    let car = || CarBuilder::new().at(3000., -2000., 17.).facing(PI / 2.);
+    let target = vec_new(4096., -1000., 800.);
+
+    let mut drive = DriveAction::new(0, target.clone(), 2300., true, false);
+    let run = harness(car()).run(&mut drive, 1);
+    assert_eq!(run.tick(0).steer, -1.);
+    assert!(run.tick(0).handbrake);
+
+    let mut drive = DriveAction::new(0, target, 2300., false, false);
+    let run = harness(car()).run(&mut drive, 1);
+    assert_eq!(run.tick(0).steer, -1.);
+    assert!(!run.tick(0).handbrake);
+}
+
+#[test]
+fn reversing_only_wins_for_targets_behind() {
+    assert!(DriveAction::should_reverse(PI, 500., 0., false));
+    assert!(!DriveAction::should_reverse(0.2, 500., 0., false));
+    assert!(!DriveAction::should_reverse(PI / 2., 500., 0., false));
+    // already flying forwards, turning around is quicker than stopping
+    assert!(!DriveAction::should_reverse(2.2, 1000., 2000., false));
+    // but keep going once we've started backing up
+    assert!(DriveAction::should_reverse(1.8, 500., -500., true));
+}
+
+#[test]
+fn boost_layer_keeps_the_reserve() {
+    let drive = || Box::new(DriveAction::new(0, vec_new(4000., -2000., 17.), 2300., false, false));
+
+    let mut blend = Blend::new(drive()).layer(1, Box::new(BoostLayer::new(0, 0)));
+    let run = harness(CarBuilder::new().boost(33)).run(&mut blend, 5);
+    run.assert_all("boost", |c| c.boost);
+
+    let mut blend = Blend::new(drive()).layer(1, Box::new(BoostLayer::new(0, 50)));
+    let run = harness(CarBuilder::new().boost(33)).run(&mut blend, 5);
+    run.assert_all("no boost below the reserve", |c| !c.boost && c.throttle == 1.);
+
+    let mut blend = Blend::new(drive()).layer(1, Box::new(BoostLayer::new(0, 0)));
+    let run = harness(CarBuilder::new().supersonic()).run(&mut blend, 5);
+    run.assert_all("no boost once supersonic", |c| !c.boost);
+}
+
+#[test]
+fn goto_speeds_up_to_make_its_arrival_time() {
+    let mut goto = GotoAction::new(vec_new(2000., -2000., 17.), None, 0);
+    goto.arrival_time = 1.;
+    let run = harness(CarBuilder::new()).run(&mut goto, 5);
+    assert!(run.finished.is_none());
+    run.assert_all("boost to get there in time", |c| c.boost);
+    assert_eq!(goto.expected_duration(), Some(1.5));
+}
+
+#[test]
+fn airdodge_jumps_twice_and_finishes() {
+    let mut dodge = AirDodgeAction::new(0, 0.1, Some(vec_new(1000., -2000., 17.)));
+    let run = harness(CarBuilder::new()).run(&mut dodge, 200);
+    assert!(run.succeeded());
+    assert!(run.tick(0).jump);
+    assert_eq!(jump_presses(&run.controllers), 2);
+}
+
+#[test]
+fn airdodge_stick_points_at_the_target_from_the_car() {
+    // facing +y, so -x is on our right
+    let world = PacketBuilder::new()
+        .car(CarBuilder::new().at(0., 0., 17.).facing(PI / 2.))
+        .world();
+    let car = world.car(0).unwrap();
+    let dodge = |x: f32, y: f32, pitch: f32, yaw: f32| {
+        let stick = AirDodgeAction::stick(car, &vec_new(x, y, 17.));
+        assert!(
+            (stick.0 - pitch).abs() < 1e-3 && (stick.1 - yaw).abs() < 1e-3,
+            "dodging at ({x}, {y}) gave {stick:?}"
+        );
+    };
+    dodge(0., 1000., -1., 0.);
+    dodge(0., -1000., 1., 0.);
+    dodge(-1000., 0., 0., 1.);
+    dodge(1000., 1000., -1., -1.);
+    dodge(-500., -1000., 1., 0.5);
+}
+
+#[test]
+fn airdodge_fails_outside_the_dodge_window() {
+    let mut dodge = AirDodgeAction::new(0, 1.3, Some(vec_new(1000., -2000., 17.)));
+    let run = harness(CarBuilder::new()).run(&mut dodge, 300);
+    assert!(run.failed_with(FailureReason::TimedOut));
+    assert_eq!(jump_presses(&run.controllers), 1);
+}
+
+#[test]
+fn recover_is_done_once_on_its_wheels() {
+    let run = harness(CarBuilder::new()).run(&mut RecoverAction::new(0, false), 10);
+    assert!(run.succeeded());
+
+    let airborne = CarBuilder::new().at(0., 0., 800.).airborne();
+    let run = harness(airborne).run(&mut RecoverAction::new(0, false), 10);
+    assert!(run.finished.is_none());
+    run.assert_all("throttle", |c| c.throttle == 1.);
+}
+
+#[test]
+fn recover_jumps_when_upside_down() {
+    let turtled = CarBuilder::new().rotation(0., 0., PI);
+    let run = harness(turtled).run(&mut RecoverAction::new(0, true), 5);
+    run.assert_all("jump", |c| c.jump);
+}
+
+#[test]
+fn reorient_gives_up_on_the_ground() {
+    let mut reorient = ReorientAction::from_uf(vec_new(0., 0., 1.), vec_new(0., 1., 0.), 0);
+    let run = harness(CarBuilder::new()).run(&mut reorient, 5);
+    assert!(run.failed_with(FailureReason::Landed));
+
+    let mut reorient = ReorientAction::from_uf(vec_new(0., 0., 1.), vec_new(0., 1., 0.), 0);
+    let airborne = CarBuilder::new().at(0., 0., 800.).airborne();
+    let run = harness(airborne).run(&mut reorient, 5);
+    run.assert_any("some rotation", |c| {
+        c.pitch.abs() + c.yaw.abs() + c.roll.abs() > 0.01
+    });
+}
+
+#[test]
+fn half_flip_backflips_cancels_and_lands() {
+    let mut half_flip = HalfFlipAction::new(0);
+    let run = harness(CarBuilder::new()).run_with(&mut half_flip, 240, |packet, tick, _| {
+        // in the air from the first jump until we fake a landing
+        packet.players.as_mut().unwrap()[0].hasWheelContact = !(1..100).contains(&tick);
+    });
+    assert!(run.succeeded());
+    assert_eq!(jump_presses(&run.controllers), 2);
+
+    // the second jump is a backflip
+    let flip = run
+        .controllers
+        .iter()
+        .enumerate()
+        .skip_while(|(_, c)| c.jump)
+        .find(|(_, c)| c.jump)
+        .map(|(n, _)| n)
+        .unwrap();
+    assert_eq!(run.tick(flip).pitch, 1.);
+    // then gets cancelled with the stick forward
+    assert!(run.controllers[flip..].iter().any(|c| c.pitch == -1.));
+}
+
+#[test]
+fn half_flip_only_for_far_targets_behind() {
+    let world = PacketBuilder::new().car(CarBuilder::new()).world();
+    let car = world.car(0).unwrap();
+    assert!(HalfFlipAction::worth_it(car, &vec_new(-3000., -2000., 17.)));
+    // close enough to back up to
+    assert!(!HalfFlipAction::worth_it(car, &vec_new(-600., -2000., 17.)));
+    // ahead
+    assert!(!HalfFlipAction::worth_it(car, &vec_new(3000., -2000., 17.)));
+}
+
+/// Sets where the car is and how it's moving, for faking a jump
+fn set_car(
+    packet: &mut GameTickPacket,
+    z: f32,
+    velocity: (f32, f32),
+    grounded: bool,
+    jumped: bool,
+) {
+    let player = &mut packet.players.as_mut().unwrap()[0];
+    player.hasWheelContact = grounded;
+    player.jumped = jumped;
+    let physics = player.physics.as_mut().unwrap();
+    physics.location.as_mut().unwrap().z = z;
+    physics.velocity = Some(vec_new(velocity.0, 0., velocity.1));
+}
+
+#[test]
+fn speed_flip_dodges_diagonally_cancels_and_reports_the_gain() {
+    let car = CarBuilder::new().velocity(1000., 0., 0.);
+    let mut flip = SpeedFlipAction::new(0, true);
+    let run = harness(car).run_with(&mut flip, 120, |packet, tick, _| match tick {
+        0 => {}
+        1..=59 => set_car(packet, 100., (1000., 0.), false, true),
+        _ => set_car(packet, 17., (1500., 0.), true, false),
+    });
+    assert!(run.succeeded());
+    assert!((flip.speed_gain().unwrap() - 500.).abs() < 1e-3);
+    run.assert_all("boost", |c| c.boost);
+    assert_eq!(jump_presses(&run.controllers), 2);
+
+    let dodge = run.controllers.iter().skip_while(|c| c.jump).find(|c| c.jump).unwrap();
+    assert_eq!((dodge.pitch, dodge.yaw), (-1., 1.));
+    run.assert_any("cancel", |c| c.pitch == 1. && c.roll == -1.);
+}
+
+#[test]
+fn wavedash_dodges_just_before_landing() {
+    let car = CarBuilder::new().velocity(1000., 0., 0.);
+    let mut wavedash = WavedashAction::new(0);
+    let run = harness(car).run_with(&mut wavedash, 120, |packet, tick, _| match tick {
+        0 => {}
+        1..=6 => set_car(packet, 17., (1000., 0.), true, true),
+        7..=20 => set_car(packet, 40., (1000., 200.), false, true),
+        21..=29 => set_car(packet, 40., (1000., -200.), false, true),
+        30 => set_car(packet, 20., (1000., -200.), false, true),
+        _ => set_car(packet, 17., (1400., 0.), true, false),
+    });
+    assert!(run.succeeded());
+    assert!((wavedash.speed_gain().unwrap() - 400.).abs() < 1e-3);
+    run.assert_any("nose up on the way up", |c| c.pitch > 0.);
+    assert!(run.tick(30).jump);
+    assert_eq!(run.tick(30).pitch, -1.);
+
+    // came back down without ever getting the dodge in
+    let car = CarBuilder::new().velocity(1000., 0., 0.);
+    let run = harness(car).run_with(&mut WavedashAction::new(0), 120, |packet, tick, _| {
+        if tick > 0 {
+            set_car(packet, 17., (1000., 0.), true, true);
+        }
+    });
+    assert!(run.failed_with(FailureReason::Landed));
+}
+
+#[test]
+fn drive_wavedashes_for_speed_without_boost() {
+    let target = vec_new(4000., -2000., 17.);
+    let car = || CarBuilder::new().velocity(1200., 0., 0.);
+
+    let mut drive = DriveAction::new(0, target.clone(), 2300., false, false);
+    drive.allow_wavedash = true;
+    let run = harness(car().boost(0)).run(&mut drive, 1);
+    assert!(run.tick(0).jump);
+
+    let mut drive = DriveAction::new(0, target.clone(), 2300., false, false);
+    drive.allow_wavedash = true;
+    let run = harness(car().boost(50)).run(&mut drive, 1);
+    assert!(!run.tick(0).jump);
+
+    // only when the caller asks for it
+    let mut drive = DriveAction::new(0, target, 2300., false, false);
+    let run = harness(car().boost(0)).run(&mut drive, 1);
+    assert!(!run.tick(0).jump);
+}
+
+#[test]
+fn kickoff_boosts_then_dodges_once_fast_enough() {
+    let car = CarBuilder::new().at(0., -4608., 17.).facing(PI / 2.);
+    let mut kickoff = BasicKickoffAction::new(0);
+    let mut kickoff_pause = ActionHarness::new(PacketBuilder::new().kickoff().car(car).build());
+    let run = kickoff_pause.run_with(&mut kickoff, 20, |packet, tick, _| {
+        if tick >= 10 {
+            let physics = packet.players.as_mut().unwrap()[0].physics.as_mut().unwrap();
+            physics.velocity = Some(vec_new(0., 1600., 0.));
+        }
+    });
+    assert_eq!(run.ticks_while(|c| c.boost && !c.jump), 10);
+    assert!(run.tick(10).jump);
+}
+
+#[test]
+fn kickoffs_drop_the_speed_flip_once_it_gains_nothing() {
+    let car = || CarBuilder::new().at(0., -4608., 17.).facing(PI / 2.);
+    let kickoff_pause = || ActionHarness::new(PacketBuilder::new().kickoff().car(car()).build());
+
+    // fast enough to flip on tick 10, then in the air until tick 30, and no faster for it
+    let mut kickoff = BasicKickoffAction::new(0);
+    let run = kickoff_pause().run_with(&mut kickoff, 40, |packet, tick, _| {
+        let player = &mut packet.players.as_mut().unwrap()[0];
+        if tick >= 10 {
+            player.physics.as_mut().unwrap().velocity = Some(vec_new(0., 1600., 0.));
+        }
+        player.hasWheelContact = !(12..30).contains(&tick);
+        player.jumped = (12..30).contains(&tick);
+        player.doubleJumped = (24..30).contains(&tick);
+    });
+    assert!(run.finished.is_none());
+    assert!(run.tick(10).jump && run.tick(10).boost);
+    assert_eq!(kickoff.speed_flip_gain(), Some(0.));
+
+    // the strategy remembers, so the next kickoff just dodges at the ball
+    let mut strategy = SoloStrategy::default();
+    strategy.on_action_finished(&kickoff, Ok(()));
+    let world = PacketBuilder::new().kickoff().car(car()).world();
+    let candidates = strategy.candidates(&world, 0, &vec![], true, None);
+    let next = candidates[0].action.as_any().downcast_ref::<BasicKickoffAction>();
+    assert!(!next.unwrap().speed_flip);
+
+    let mut kickoff = BasicKickoffAction::new(0);
+    kickoff.speed_flip = false;
+    let run = kickoff_pause().run_with(&mut kickoff, 20, |packet, tick, _| {
+        if tick >= 10 {
+            let physics = packet.players.as_mut().unwrap()[0].physics.as_mut().unwrap();
+            physics.velocity = Some(vec_new(0., 1600., 0.));
+        }
+    });
+    assert!(run.tick(10).jump && !run.tick(10).boost);
+}
+
+#[test]
+fn strike_without_an_intercept_fails() {
+    let mut strike = StrikeAction::new(0, StrikeKind::DriveThrough, None);
+    let run = harness(CarBuilder::new()).run(&mut strike, 5);
+    assert!(run.failed_with(FailureReason::TargetUnreachable));
+}
+
+#[test]
+fn aerial_jumps_for_a_ball_it_can_reach() {
+    let car = CarBuilder::new().facing(PI / 2.).boost(100);
+    let predictions = PredictionBuilder::new()
+        .ball_at(0., -1000., 1200.)
+        .floating()
+        .build();
+    let mut aerial = AerialAction::new(0);
+    let run = harness(car).predictions(predictions).run(&mut aerial, 5);
+    assert!(run.finished.is_none());
+    run.assert_all("jump", |c| c.jump);
+    assert!(aerial.target.is_some());
+    assert!(aerial.arrival > 1. && aerial.arrival < 4.);
+
+    // same ball, no boost to get up there
+    let predictions = PredictionBuilder::new()
+        .ball_at(0., -1000., 1200.)
+        .floating()
+        .build();
+    let car = CarBuilder::new().facing(PI / 2.).boost(0);
+    let run = harness(car).predictions(predictions).run(&mut AerialAction::new(0), 5);
+    assert!(run.failed_with(FailureReason::TargetUnreachable));
+}
+
+#[test]
+fn aerial_gives_up_early_when_it_cant_make_it() {
+    let car = || {
+        CarBuilder::new()
+            .at(0., -2000., 500.)
+            .facing(PI / 2.)
+            .airborne()
+            .jumped(true, false)
+            .boost(5)
+    };
+    let target = vec_new(0., -1000., 1200.);
+
+    let mut aerial = AerialAction::new(0).with_target(target.clone(), 2.);
+    let run = harness(car()).run(&mut aerial, 5);
+    assert!(run.failed_with(FailureReason::OutOfBoost));
+
+    let mut aerial = AerialAction::new(0).with_target(target, 0.5);
+    let run = harness(car()).run(&mut aerial, 5);
+    assert!(run.failed_with(FailureReason::TimedOut));
+}
+
+#[test]
+fn aerial_steers_sanely_straight_up_and_right_on_target() {
+    let finite = |c: &ControllerState| {
+        c.roll.is_finite() && c.pitch.is_finite() && c.yaw.is_finite()
+    };
+    let car = || {
+        CarBuilder::new()
+            .at(0., -2000., 500.)
+            .airborne()
+            .jumped(true, false)
+            .boost(100)
+    };
+
+    // the ball's right above us, with the roof pointing at it
+    let mut aerial = AerialAction::new(0).with_target(vec_new(0., -2000., 1200.), 2.);
+    let run = harness(car()).run(&mut aerial, 5);
+    assert!(run.finished.is_none());
+    run.assert_all("finite inputs", finite);
+
+    // right where we'll be anyway, so there's nowhere to point
+    let mut aerial = AerialAction::new(0).with_target(vec_new(0., -2000., 418.75), 0.5);
+    let run = harness(car()).run(&mut aerial, 5);
+    assert!(run.finished.is_none());
+    run.assert_all("finite inputs", finite);
+}
+
+#[test]
+fn intercepts_a_ball_it_can_reach() {
+    let world = PacketBuilder::new()
+        .car(CarBuilder::new().facing(PI / 2.))
+        .world();
+    let car = world.car(0).unwrap();
+
+    let predictions = PredictionBuilder::new().build();
+    let intercept = Intercept::new(car, 0., &predictions, &world.ball, false, None);
+    assert!(intercept.is_viable);
+    assert!(intercept.location.dist(&world.ball.location) < 10.);
+
+    // a ball rolling away faster than we can drive
+    let predictions = PredictionBuilder::new()
+        .ball_velocity(0., 3000., 0.)
+        .seconds(1.)
+        .build();
+    let intercept = Intercept::new(car, 0., &predictions, &world.ball, false, None);
+    assert!(!intercept.is_viable);
+}
+
+#[test]
+fn predictions_fall_and_bounce() {
+    let predictions = PredictionBuilder::new()
+        .start(10.)
+        .ball_at(0., 0., 1000.)
+        .ball_velocity(500., 0., 0.)
+        .seconds(3.)
+        .build();
+    assert_eq!(predictions.len(), 360);
+    assert!((predictions[119].gameSeconds - 11.).abs() < 1e-3);
+
+    let heights: Vec<f32> = predictions
+        .iter()
+        .map(|slice| slice.physics.as_ref().unwrap().location.as_ref().unwrap().z)
+        .collect();
+    let lowest = heights.iter().cloned().fold(f32::MAX, f32::min);
+    assert!(lowest >= 92.);
+    // came back up after hitting the floor
+    assert!(heights.last().unwrap() > &lowest);
+
+    let last = predictions.last().unwrap().physics.as_ref().unwrap();
+    let last = last.location.as_ref().unwrap();
+    assert!((last.x - 1500.).abs() < 10.);
+}
+
+#[test]
+fn builder_cars_come_out_in_order() {
+    let world = PacketBuilder::new()
+        .car(CarBuilder::new().name("blue"))
+        .car(CarBuilder::new().name("orange").team(1).at(0., 2000., 17.))
+        .touch(1, 1, 3.)
+        .score(2, 1)
+        .pad_taken(0, 4.)
+        .world();
+    let orange: &Car = world.car(1).unwrap();
+    assert_eq!(orange.name, "orange");
+    assert_eq!(orange.location.dist(&vec_new(0., 2000., 17.)), 0.);
+    assert_eq!(world.ball.latest_touch.as_ref().unwrap().player_index, 1);
+    assert_eq!(world.game.score, [2, 1]);
+    assert_eq!(world.boost_pads.len(), 34);
+    assert!(!world.boost_pads[0].is_active);
+}
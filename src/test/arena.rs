@@ -0,0 +1,44 @@
+use crate::utils::{
+    arena::{Arena, Surface},
+    math::math::{vec_new, Vec3},
+};
+
+#[test]
+fn positions_belong_to_the_closest_surface() {
+    assert_eq!(Arena::surface(&vec_new(0., 0., 17.)), Surface::Floor);
+    assert_eq!(Arena::surface(&vec_new(4000., 0., 800.)), Surface::SideWall(1.));
+    assert_eq!(Arena::surface(&vec_new(0., -5050., 1000.)), Surface::BackWall(-1.));
+    assert_eq!(Arena::surface(&vec_new(0., 0., 2000.)), Surface::Ceiling);
+
+    assert_eq!(Surface::from_up(&vec_new(0., 0., 1.)), Surface::Floor);
+    assert_eq!(Surface::from_up(&vec_new(-1., 0., 0.)), Surface::SideWall(1.));
+    assert_eq!(Surface::from_up(&vec_new(0., 0.9, 0.3)), Surface::BackWall(-1.));
+}
+
+#[test]
+fn only_surfaces_outside_the_goals_are_drivable() {
+    assert!(Arena::drivable(&vec_new(4000., 0., 800.)));
+    assert!(Arena::drivable(&vec_new(2000., 5050., 300.)));
+    // in the goal
+    assert!(!Arena::drivable(&vec_new(0., 5050., 300.)));
+    // in the air
+    assert!(!Arena::drivable(&vec_new(0., 0., 1000.)));
+}
+
+#[test]
+fn unfolding_lays_surfaces_out_flat() {
+    // up the side wall is past the wall, seen from the floor
+    let wall = vec_new(4096., 1000., 500.);
+    let unfolded = Surface::SideWall(1.).unfold(&wall, Surface::Floor);
+    assert!(unfolded.dist(&vec_new(4596., 1000., 0.)) < 1e-3);
+
+    // and the floor is below the wall, seen from the wall
+    let floor = vec_new(3000., 1000., 0.);
+    let unfolded = Surface::Floor.unfold(&floor, Surface::SideWall(1.));
+    assert!(unfolded.dist(&vec_new(4096., 1000., -1096.)) < 1e-3);
+
+    // the ceiling goes by way of a wall
+    let ceiling = vec_new(3000., 0., 2044.);
+    let unfolded = Surface::Ceiling.unfold(&ceiling, Surface::Floor);
+    assert!(unfolded.dist(&vec_new(4096. + 2044. + 1096., 0., 0.)) < 1e-3);
+}
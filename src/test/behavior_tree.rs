@@ -0,0 +1,248 @@
+use rlbot_lib::rlbot::{ControllerState, PredictionSlice, RenderMessage};
+
+use crate::{
+    actions::{
+        action::{Action, ActionResult, FailureReason},
+        behavior_tree::{Blend, Condition, Parallel, Retry, Selector, Sequence, Timeout},
+        boost_layer::BoostLayer,
+    },
+    utils::{channels::channels::Channel, world::world::World, ActionTickResult},
+};
+
+use super::packets::tick;
+
+/// Sets the throttle to `throttle` for `steps` ticks, then succeeds or fails
+#[derive(Clone, Debug)]
+struct Leaf {
+    throttle: f32,
+    steps: u32,
+    fails: bool,
+}
+
+fn leaf(throttle: f32, steps: u32, fails: bool) -> Box<dyn Action> {
+    Box::new(Leaf {
+        throttle,
+        steps,
+        fails,
+    })
+}
+
+impl Action for Leaf {
+    fn step(
+        &mut self,
+        _world: &World,
+        controller: ControllerState,
+        _predictions: &Vec<PredictionSlice>,
+        _dt: f32,
+    ) -> ActionResult {
+        if self.steps == 0 {
+            return if self.fails {
+                ActionResult::Failed(FailureReason::TargetUnreachable)
+            } else {
+                ActionResult::Success
+            };
+        }
+        self.steps -= 1;
+        ActionResult::InProgress(ActionTickResult::from(ControllerState {
+            throttle: self.throttle,
+            ..controller
+        }))
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        vec![]
+    }
+
+    fn interruptible(&self) -> bool {
+        true
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        format!("Leaf {}", self.throttle)
+    }
+}
+
+/// Step `action` until it finishes, returning the throttle it used on every tick
+fn run(action: &mut dyn Action) -> (Vec<f32>, ActionResult) {
+    let world = World::from_packet(&tick(0.)).unwrap();
+    let mut throttles = vec![];
+    for _ in 0..100 {
+        match action.step(&world, ControllerState::default(), &vec![], 1. / 120.) {
+            ActionResult::InProgress(res) => throttles.push(res.controller.throttle),
+            result => return (throttles, result),
+        }
+    }
+    panic!("{} never finished", action.name());
+}
+
+#[test]
+fn sequence_runs_children_in_order() {
+    let mut sequence = Sequence::new(vec![leaf(1., 2, false), leaf(-1., 1, false)]);
+    let (throttles, result) = run(&mut sequence);
+    assert_eq!(throttles, vec![1., 1., -1.]);
+    assert!(matches!(result, ActionResult::Success));
+}
+
+#[test]
+fn sequence_stops_at_the_first_failure() {
+    let mut sequence = Sequence::new(vec![leaf(1., 1, true), leaf(-1., 1, false)]);
+    let (throttles, result) = run(&mut sequence);
+    assert_eq!(throttles, vec![1.]);
+    assert!(matches!(
+        result,
+        ActionResult::Failed(FailureReason::TargetUnreachable)
+    ));
+}
+
+#[test]
+fn selector_falls_through_failures() {
+    let mut selector = Selector::new(vec![
+        Box::new(Condition::new("never", Box::new(|_: &World| false))),
+        leaf(1., 1, true),
+        leaf(0.5, 2, false),
+    ]);
+    let (throttles, result) = run(&mut selector);
+    assert_eq!(throttles, vec![1., 0.5, 0.5]);
+    assert!(matches!(result, ActionResult::Success));
+}
+
+#[test]
+fn parallel_any_finishes_with_the_first_child() {
+    let mut parallel = Parallel::any(vec![leaf(1., 5, false), leaf(0.5, 2, false)]);
+    let (throttles, result) = run(&mut parallel);
+    // the later child wins the throttle while it's running
+    assert_eq!(throttles, vec![0.5, 0.5]);
+    assert!(matches!(result, ActionResult::Success));
+
+    let mut parallel = Parallel::all(vec![leaf(1., 3, false), leaf(0.5, 1, false)]);
+    let (throttles, _) = run(&mut parallel);
+    assert_eq!(throttles, vec![0.5, 1., 1.]);
+}
+
+#[test]
+fn timeout_and_retry() {
+    let mut timeout = Timeout::new(0.105, leaf(1., 50, false));
+    let (throttles, result) = run(&mut timeout);
+    assert_eq!(throttles.len(), 12);
+    assert!(matches!(result, ActionResult::Failed(FailureReason::TimedOut)));
+
+    let mut retry = Retry::new(3, Box::new(|| leaf(1., 2, true)));
+    let (throttles, result) = run(&mut retry);
+    assert_eq!(throttles.len(), 6);
+    assert!(matches!(result, ActionResult::Failed(_)));
+}
+
+/// Full reverse, boost and jump, but only on `channels`
+#[derive(Clone, Debug)]
+struct Layer {
+    channels: Vec<Channel>,
+}
+
+impl Action for Layer {
+    fn step(
+        &mut self,
+        _world: &World,
+        controller: ControllerState,
+        _predictions: &Vec<PredictionSlice>,
+        _dt: f32,
+    ) -> ActionResult {
+        ActionResult::InProgress(ActionTickResult::from(ControllerState {
+            throttle: -1.,
+            boost: true,
+            jump: true,
+            ..controller
+        }))
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        vec![]
+    }
+
+    fn interruptible(&self) -> bool {
+        true
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        String::from("Layer")
+    }
+
+    fn channels(&self) -> Vec<Channel> {
+        self.channels.clone()
+    }
+}
+
+fn step_once(action: &mut dyn Action) -> ControllerState {
+    let world = World::from_packet(&tick(0.)).unwrap();
+    match action.step(&world, ControllerState::default(), &vec![], 1. / 120.) {
+        ActionResult::InProgress(res) => res.controller,
+        _ => panic!("{} finished early", action.name()),
+    }
+}
+
+#[test]
+fn blend_layers_only_touch_their_channels() {
+    let mut blend = Blend::new(leaf(1., 2, false)).layer(
+        1,
+        Box::new(Layer {
+            channels: vec![Channel::Boost],
+        }),
+    );
+    let controller = step_once(&mut blend);
+    assert_eq!(controller.throttle, 1.);
+    assert!(controller.boost);
+    assert!(!controller.jump);
+
+    // the base still decides when we're done
+    let (throttles, result) = run(&mut blend);
+    assert_eq!(throttles, vec![1.]);
+    assert!(matches!(result, ActionResult::Success));
+}
+
+#[test]
+fn blend_priority_decides_shared_channels() {
+    let mut blend = Blend::new(leaf(1., 5, false))
+        .layer(
+            2,
+            Box::new(Layer {
+                channels: vec![Channel::Drive],
+            }),
+        )
+        // holds a reserve bigger than the 33 boost the car in the packet has
+        .layer(1, Box::new(BoostLayer::new(0, 50)))
+        .layer(
+            0,
+            Box::new(Layer {
+                channels: vec![Channel::Boost],
+            }),
+        );
+    let controller = step_once(&mut blend);
+    assert_eq!(controller.throttle, -1.);
+    assert!(!controller.boost);
+}
+
+#[test]
+fn forked_actions_run_on_their_own() {
+    let mut sequence: Box<dyn Action> = Box::new(Sequence::new(vec![
+        leaf(1., 2, false),
+        Box::new(Retry::new(2, Box::new(|| leaf(-1., 1, true)))),
+    ]));
+    step_once(sequence.as_mut());
+
+    // the fork picks up where the original was, and stepping it leaves the original alone
+    let mut fork = sequence.clone();
+    let (throttles, result) = run(fork.as_mut());
+    assert_eq!(throttles, vec![1., -1., -1.]);
+    assert!(matches!(result, ActionResult::Failed(_)));
+
+    let (throttles, _) = run(sequence.as_mut());
+    assert_eq!(throttles, vec![1., -1., -1.]);
+    assert!(format!("{sequence:?}").contains("Leaf"));
+}
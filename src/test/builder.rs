@@ -0,0 +1,309 @@
+// Fluent builders for the packets the bot gets from RLBot, so a test can set up exactly the
+// situation it wants in a couple of lines.
+
+use rlbot_lib::rlbot::{
+    BallInfo, BoostPadState, GameInfo, GameTickPacket, Physics, PlayerInfo, PredictionSlice,
+    Rotator, TeamInfo, Touch, Vector3,
+};
+
+use crate::utils::{
+    math::math::{vec_new, Vec3},
+    world::world::World,
+};
+
+/// How many boost pads there are on a standard map
+const BOOST_PADS: usize = 34;
+
+/// RLBot gives us 120 prediction slices a second
+pub const SLICES_PER_SECOND: f32 = 120.;
+
+fn physics(location: Vector3, velocity: Vector3, rotation: Rotator) -> Option<Box<Physics>> {
+    Some(Box::new(Physics {
+        location: Some(location),
+        rotation: Some(rotation),
+        velocity: Some(velocity),
+        angularVelocity: Some(Vector3::default()),
+    }))
+}
+
+/// One car for a `PacketBuilder`. Starts out on its wheels in front of the blue goal, facing the
+/// side wall, sitting still with 33 boost
+pub struct CarBuilder {
+    player: PlayerInfo,
+}
+
+impl CarBuilder {
+    pub fn new() -> CarBuilder {
+        CarBuilder {
+            player: PlayerInfo {
+                physics: physics(
+                    vec_new(0., -2000., 17.),
+                    Vector3::default(),
+                    Rotator::default(),
+                ),
+                hasWheelContact: true,
+                name: Some(String::from("BOT1")),
+                boost: 33,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn physics(&mut self) -> &mut Physics {
+        self.player.physics.as_mut().unwrap()
+    }
+
+    pub fn at(mut self, x: f32, y: f32, z: f32) -> CarBuilder {
+        self.physics().location = Some(vec_new(x, y, z));
+        self
+    }
+
+    pub fn velocity(mut self, x: f32, y: f32, z: f32) -> CarBuilder {
+        self.physics().velocity = Some(vec_new(x, y, z));
+        self
+    }
+
+    pub fn angular_velocity(mut self, x: f32, y: f32, z: f32) -> CarBuilder {
+        self.physics().angularVelocity = Some(vec_new(x, y, z));
+        self
+    }
+
+    /// Yaw 0 faces +x, going counterclockwise seen from above
+    pub fn facing(self, yaw: f32) -> CarBuilder {
+        self.rotation(0., yaw, 0.)
+    }
+
+    pub fn rotation(mut self, pitch: f32, yaw: f32, roll: f32) -> CarBuilder {
+        self.physics().rotation = Some(Rotator { pitch, yaw, roll });
+        self
+    }
+
+    pub fn boost(mut self, boost: i32) -> CarBuilder {
+        self.player.boost = boost;
+        self
+    }
+
+    pub fn team(mut self, team: i32) -> CarBuilder {
+        self.player.team = team;
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> CarBuilder {
+        self.player.name = Some(String::from(name));
+        self
+    }
+
+    /// Off the ground. Doesn't move the car, so set a height with `at` too
+    pub fn airborne(mut self) -> CarBuilder {
+        self.player.hasWheelContact = false;
+        self
+    }
+
+    pub fn jumped(mut self, jumped: bool, double_jumped: bool) -> CarBuilder {
+        self.player.jumped = jumped;
+        self.player.doubleJumped = double_jumped;
+        self
+    }
+
+    pub fn supersonic(mut self) -> CarBuilder {
+        self.player.isSupersonic = true;
+        self
+    }
+
+    pub fn demolished(mut self) -> CarBuilder {
+        self.player.isDemolished = true;
+        self
+    }
+}
+
+/// Builds a GameTickPacket. Starts out with an active round, no cars, the ball sitting on the
+/// kickoff spot and every boost pad up
+pub struct PacketBuilder {
+    packet: GameTickPacket,
+}
+
+impl PacketBuilder {
+    pub fn new() -> PacketBuilder {
+        PacketBuilder {
+            packet: GameTickPacket {
+                players: Some(vec![]),
+                ball: Some(Box::new(BallInfo {
+                    physics: physics(vec_new(0., 0., 93.), Vector3::default(), Rotator::default()),
+                    ..Default::default()
+                })),
+                gameInfo: Some(Box::new(GameInfo {
+                    isRoundActive: true,
+                    ..Default::default()
+                })),
+                boostPadStates: Some(vec![
+                    BoostPadState {
+                        isActive: true,
+                        timer: 0.,
+                    };
+                    BOOST_PADS
+                ]),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn game_info(&mut self) -> &mut GameInfo {
+        self.packet.gameInfo.as_mut().unwrap()
+    }
+
+    fn ball(&mut self) -> &mut BallInfo {
+        self.packet.ball.as_mut().unwrap()
+    }
+
+    pub fn time(mut self, seconds_elapsed: f32) -> PacketBuilder {
+        self.game_info().secondsElapsed = seconds_elapsed;
+        self
+    }
+
+    /// Adds a car. Cars get their index in the order they're added
+    pub fn car(mut self, car: CarBuilder) -> PacketBuilder {
+        self.packet.players.get_or_insert_with(Vec::new).push(car.player);
+        self
+    }
+
+    pub fn ball_at(mut self, x: f32, y: f32, z: f32) -> PacketBuilder {
+        self.ball().physics.as_mut().unwrap().location = Some(vec_new(x, y, z));
+        self
+    }
+
+    pub fn ball_velocity(mut self, x: f32, y: f32, z: f32) -> PacketBuilder {
+        self.ball().physics.as_mut().unwrap().velocity = Some(vec_new(x, y, z));
+        self
+    }
+
+    /// The ball was last touched by the car at `player_index`, at `game_seconds`
+    pub fn touch(mut self, player_index: i32, team: i32, game_seconds: f32) -> PacketBuilder {
+        let location = self.ball().physics.as_ref().unwrap().location.clone();
+        self.ball().latestTouch = Some(Box::new(Touch {
+            playerIndex: player_index,
+            team,
+            gameSeconds: game_seconds,
+            location,
+            ..Default::default()
+        }));
+        self
+    }
+
+    /// Counting down before a kickoff
+    pub fn kickoff(mut self) -> PacketBuilder {
+        self.game_info().isKickoffPause = true;
+        self
+    }
+
+    /// Takes the pad at `index` (see `LOCATIONS` in utils/boost.rs), to come back in `timer`
+    /// seconds
+    pub fn pad_taken(mut self, index: usize, timer: f32) -> PacketBuilder {
+        if let Some(pad) = self.packet.boostPadStates.as_mut().unwrap().get_mut(index) {
+            pad.isActive = false;
+            pad.timer = timer;
+        }
+        self
+    }
+
+    pub fn score(mut self, blue: i32, orange: i32) -> PacketBuilder {
+        self.packet.teams = Some(vec![
+            TeamInfo {
+                teamIndex: 0,
+                score: blue,
+            },
+            TeamInfo {
+                teamIndex: 1,
+                score: orange,
+            },
+        ]);
+        self
+    }
+
+    pub fn build(self) -> GameTickPacket {
+        self.packet
+    }
+
+    /// The World the bot would parse out of this packet
+    pub fn world(self) -> World {
+        World::from_packet(&self.packet).unwrap()
+    }
+}
+
+/// Builds a ball prediction, the way RLBot would send it: a slice every 1/120s. The ball flies
+/// in a straight line with gravity, and bounces off the floor losing some speed. Nothing else is
+/// simulated, so keep predictions short or away from the walls
+pub struct PredictionBuilder {
+    start_time: f32,
+    location: Vector3,
+    velocity: Vector3,
+    gravity: bool,
+    seconds: f32,
+}
+
+impl PredictionBuilder {
+    const GRAVITY: f32 = -650.;
+    const BALL_RADIUS: f32 = 92.75;
+    /// How much vertical speed the ball keeps after a bounce
+    const RESTITUTION: f32 = 0.6;
+
+    /// A ball sitting still on the kickoff spot, predicted for 6 seconds from time 0
+    pub fn new() -> PredictionBuilder {
+        PredictionBuilder {
+            start_time: 0.,
+            location: vec_new(0., 0., 93.),
+            velocity: Vector3::default(),
+            gravity: true,
+            seconds: 6.,
+        }
+    }
+
+    /// Game time of the first slice, should match the packet's secondsElapsed
+    pub fn start(mut self, seconds_elapsed: f32) -> PredictionBuilder {
+        self.start_time = seconds_elapsed;
+        self
+    }
+
+    pub fn ball_at(mut self, x: f32, y: f32, z: f32) -> PredictionBuilder {
+        self.location = vec_new(x, y, z);
+        self
+    }
+
+    pub fn ball_velocity(mut self, x: f32, y: f32, z: f32) -> PredictionBuilder {
+        self.velocity = vec_new(x, y, z);
+        self
+    }
+
+    /// Ignore gravity, so the ball keeps going in a straight line
+    pub fn floating(mut self) -> PredictionBuilder {
+        self.gravity = false;
+        self
+    }
+
+    pub fn seconds(mut self, seconds: f32) -> PredictionBuilder {
+        self.seconds = seconds;
+        self
+    }
+
+    pub fn build(self) -> Vec<PredictionSlice> {
+        let dt = 1. / SLICES_PER_SECOND;
+        let mut location = self.location.clone();
+        let mut velocity = self.velocity.clone();
+        let slices = (self.seconds * SLICES_PER_SECOND) as usize;
+        (1..=slices)
+            .map(|i| {
+                if self.gravity {
+                    velocity.z += PredictionBuilder::GRAVITY * dt;
+                }
+                location = location.add(&velocity.scale(dt));
+                if location.z < PredictionBuilder::BALL_RADIUS {
+                    location.z = PredictionBuilder::BALL_RADIUS;
+                    velocity.z = -velocity.z * PredictionBuilder::RESTITUTION;
+                }
+                PredictionSlice {
+                    gameSeconds: self.start_time + i as f32 * dt,
+                    physics: physics(location.clone(), velocity.clone(), Rotator::default()),
+                }
+            })
+            .collect()
+    }
+}
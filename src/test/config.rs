@@ -0,0 +1,46 @@
+use crate::config::config::Config;
+
+#[test]
+fn empty_config_uses_defaults() {
+    let config = Config::parse("").unwrap();
+    assert_eq!(config.connection.address, "127.0.0.1:23234");
+    assert_eq!(config.bot.car_id, 0);
+    assert_eq!(config.bot.strategy, "solo");
+    assert_eq!(config.tuning.boost_grab_threshold, 30);
+}
+
+#[test]
+fn partial_sections_keep_other_defaults() {
+    let config = Config::parse(
+        r#"
+        [bot]
+        car_id = 3
+
+        [tuning]
+        goto_lerp_t = 0.4
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.bot.car_id, 3);
+    assert!(config.bot.debug_rendering);
+    assert_eq!(config.tuning.goto_lerp_t, 0.4);
+    assert_eq!(config.tuning.kickoff_center_speed, 1550.);
+}
+
+#[test]
+fn example_config_matches_defaults() {
+    let config = Config::parse(include_str!("../../bot.toml")).unwrap();
+    let defaults = Config::default();
+    assert_eq!(config.connection.address, defaults.connection.address);
+    assert_eq!(config.bot.strategy, defaults.bot.strategy);
+    assert_eq!(config.tuning.goto_lerp_t, defaults.tuning.goto_lerp_t);
+    assert_eq!(
+        config.tuning.kickoff_offcenter_speed,
+        defaults.tuning.kickoff_offcenter_speed
+    );
+}
+
+#[test]
+fn typos_are_errors() {
+    assert!(Config::parse("[tuning]\nboost_grab_threshhold = 20").is_err());
+}
@@ -0,0 +1,92 @@
+use rlbot_lib::{
+    rlbot::{BallPrediction, QuickChat, QuickChatSelection},
+    Packet,
+};
+
+use crate::{
+    bot::bot::Agent,
+    session::session::{Backoff, Session, Status},
+    strategies::solo_strategy::SoloStrategy,
+};
+
+use super::{mock_server::mock_server::MockServer, packets::tick};
+
+fn script(ticks: usize) -> Vec<Packet> {
+    let mut script = vec![];
+    for i in 0..ticks {
+        script.push(Packet::BallPrediction(BallPrediction {
+            slices: Some(vec![]),
+        }));
+        script.push(Packet::GameTickPacket(tick(i as f32 / 120.)));
+    }
+    script
+}
+
+fn connect(server: &MockServer) -> Session {
+    let agent = Agent::new(false, 0, Box::new(SoloStrategy::default()));
+    let mut session =
+        Session::new(&server.addr(), Backoff::default(), agent).expect("connect to mock server");
+    session.ready().unwrap();
+    session
+}
+
+#[test]
+fn responds_to_every_tick() {
+    let ticks = 30;
+    let script = script(ticks);
+    let steps = script.len();
+    let server = MockServer::start(script);
+
+    let mut session = connect(&server);
+    for _ in 0..steps {
+        assert_eq!(session.step().unwrap(), Status::Running);
+    }
+    assert_eq!(session.tick_count, ticks as u64);
+    drop(session);
+
+    let received = server.finish();
+    assert_eq!(received.ready.len(), 1);
+    assert!(received.ready[0].wantsBallPredictions);
+    assert_eq!(received.inputs.len(), ticks);
+    assert_eq!(received.renders.len(), ticks);
+    assert!(received.inputs.iter().all(|input| input.playerIndex == 0));
+    assert!(received.game_states.is_empty());
+}
+
+#[test]
+fn thanks_for_the_save() {
+    let server = MockServer::start(vec![Packet::QuickChat(QuickChat {
+        quickChatSelection: QuickChatSelection::Compliments_WhatASave,
+        ..QuickChat::default()
+    })]);
+
+    let mut session = connect(&server);
+    session.step().unwrap();
+    drop(session);
+
+    let received = server.finish();
+    assert_eq!(received.quick_chats.len(), 1);
+    assert_eq!(
+        received.quick_chats[0].quickChatSelection,
+        QuickChatSelection::Compliments_Thanks
+    );
+    assert!(received.inputs.is_empty());
+}
+
+#[test]
+fn stops_when_the_match_ends() {
+    let mut script = script(5);
+    let mut last = tick(5. / 120.);
+    last.gameInfo.as_mut().unwrap().isMatchEnded = true;
+    script.push(Packet::GameTickPacket(last));
+    let server = MockServer::start(script);
+
+    let mut session = connect(&server);
+    session.run().expect("the match should end cleanly");
+    assert_eq!(session.tick_count, 6);
+    drop(session);
+
+    // the bot doesn't respond to the tick that ends the match
+    let received = server.finish();
+    assert_eq!(received.inputs.len(), 5);
+}
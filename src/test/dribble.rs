@@ -0,0 +1,133 @@
+use crate::{
+    actions::{
+        action::{Action, FailureReason},
+        dribble_action::DribbleAction,
+    },
+    utils::math::math::vec_new,
+};
+
+use super::{
+    builder::{CarBuilder, PacketBuilder, PredictionBuilder},
+    harness::ActionHarness,
+};
+
+/// How far forwards of the middle of the roof the dribble keeps the ball
+const HOLD: f32 = 15.;
+
+/// Our car facing +x in front of the blue goal, with the ball at `x`, `y` and `z` from it.
+/// The target's straight ahead
+fn carrying(x: f32, y: f32, z: f32) -> ActionHarness {
+    ActionHarness::new(
+        PacketBuilder::new()
+            .car(CarBuilder::new())
+            .ball_at(x, -2000. + y, 17. + z)
+            .build(),
+    )
+}
+
+fn dribble() -> DribbleAction {
+    DribbleAction::new(0, vec_new(4000., -2000., 93.))
+}
+
+#[test]
+fn ball_on_the_roof_counts_as_on_the_car() {
+    let world = PacketBuilder::new()
+        .car(CarBuilder::new())
+        .ball_at(10., -2000., 127.)
+        .world();
+    assert!(DribbleAction::on_car(world.car(0).unwrap(), &world.ball));
+
+    let world = PacketBuilder::new()
+        .car(CarBuilder::new())
+        .ball_at(0., -2000., 400.)
+        .world();
+    assert!(!DribbleAction::on_car(world.car(0).unwrap(), &world.ball));
+}
+
+#[test]
+fn carrying_gets_back_under_the_ball() {
+    // ball's getting ahead of us, speed up
+    let run = carrying(60., 0., 110.).run(&mut dribble(), 1);
+    assert!(run.tick(0).throttle > 0.);
+
+    // it's behind us, slow down
+    let run = carrying(-60., 0., 110.).run(&mut dribble(), 1);
+    assert!(run.tick(0).throttle < 0.);
+
+    // it's off to the left, which is -y facing +x, so steer left
+    let run = carrying(HOLD, -40., 110.).run(&mut dribble(), 1);
+    assert!(run.tick(0).steer < 0.);
+}
+
+#[test]
+fn dribble_fails_when_the_ball_falls_off() {
+    let mut dribble = dribble();
+    let run = carrying(HOLD, 0., 110.).run_with(&mut dribble, 10, |packet, tick, _| {
+        if tick == 5 {
+            let ball = packet.ball.as_mut().unwrap().physics.as_mut().unwrap();
+            ball.location = Some(vec_new(-300., -2000., 93.));
+        }
+    });
+    assert!(run.failed_with(FailureReason::BallLost));
+    assert_eq!(run.controllers.len(), 5);
+    assert_eq!(dribble.name(), "DribbleAction (carrying)");
+}
+
+#[test]
+fn dribble_fails_when_an_opponent_comes_for_the_ball() {
+    let packet = PacketBuilder::new()
+        .car(CarBuilder::new())
+        .car(
+            CarBuilder::new()
+                .team(1)
+                .at(600., -2000., 17.)
+                .facing(std::f32::consts::PI)
+                .velocity(-1000., 0., 0.),
+        )
+        .ball_at(HOLD, -2000., 127.)
+        .build();
+    let run = ActionHarness::new(packet).run(&mut dribble(), 5);
+    assert!(run.failed_with(FailureReason::Challenged));
+}
+
+#[test]
+fn dribble_drives_to_catch_a_slow_ball() {
+    let packet = PacketBuilder::new()
+        .car(CarBuilder::new())
+        .ball_at(1500., -2000., 93.)
+        .ball_velocity(300., 0., 0.)
+        .build();
+    let predictions = PredictionBuilder::new()
+        .ball_at(1500., -2000., 93.)
+        .ball_velocity(300., 0., 0.)
+        .build();
+    let mut dribble = dribble();
+    let run = ActionHarness::new(packet).predictions(predictions).run(&mut dribble, 5);
+    assert!(run.finished.is_none());
+    run.assert_all("driving at it", |c| c.throttle > 0.);
+    assert_eq!(dribble.name(), "DribbleAction (catching)");
+}
+
+#[test]
+fn dribble_plans_the_catch_again_when_the_ball_goes_elsewhere() {
+    let rolling = |x: f32, y: f32, vx: f32, vy: f32| {
+        let packet = PacketBuilder::new()
+            .car(CarBuilder::new())
+            .ball_at(x, y, 93.)
+            .ball_velocity(vx, vy, 0.)
+            .build();
+        let predictions = PredictionBuilder::new()
+            .ball_at(x, y, 93.)
+            .ball_velocity(vx, vy, 0.)
+            .build();
+        ActionHarness::new(packet).predictions(predictions)
+    };
+    let mut dribble = dribble();
+    let run = rolling(1500., -2000., 300., 0.).run(&mut dribble, 1);
+    assert!(run.tick(0).steer.abs() < 0.1);
+
+    // something knocked it off to our right, which is +y facing +x
+    let run = rolling(0., -500., 0., 300.).run(&mut dribble, 1);
+    assert!(run.finished.is_none());
+    assert!(run.tick(0).steer > 0.);
+}
@@ -1,5 +0,0 @@
-pub mod game_state {
-    pub fn set_car_location(position: Vector3) {
-        
-    }
-}
@@ -0,0 +1,122 @@
+use rlbot_lib::rlbot::{ControllerState, GameTickPacket, PredictionSlice};
+
+use crate::{
+    actions::action::{Action, ActionResult, FailureReason},
+    utils::world::world::World,
+};
+
+/// Steps an action tick by tick and writes down what it did. The game clock moves forward every
+/// tick, but nothing else does unless the test moves it with `run_with`
+pub struct ActionHarness {
+    packet: GameTickPacket,
+    predictions: Vec<PredictionSlice>,
+    pub dt: f32,
+}
+
+/// What an action did over a run
+pub struct Run {
+    /// The inputs from every tick the action was still going
+    pub controllers: Vec<ControllerState>,
+    /// How it ended, or None if it was still going when the run stopped
+    pub finished: Option<Result<(), FailureReason>>,
+}
+
+impl ActionHarness {
+    pub fn new(packet: GameTickPacket) -> ActionHarness {
+        ActionHarness {
+            packet,
+            predictions: vec![],
+            dt: 1. / 120.,
+        }
+    }
+
+    pub fn predictions(mut self, predictions: Vec<PredictionSlice>) -> ActionHarness {
+        self.predictions = predictions;
+        self
+    }
+
+    /// Start `action` and step it for up to `ticks` ticks, stopping early if it finishes
+    pub fn run(&mut self, action: &mut dyn Action, ticks: u32) -> Run {
+        self.run_with(action, ticks, |_, _, _| {})
+    }
+
+    /// Like `run`, but `update` gets to change the packet before every tick, to fake the car
+    /// moving or landing. It's given the tick number and the inputs from the tick before
+    pub fn run_with(
+        &mut self,
+        action: &mut dyn Action,
+        ticks: u32,
+        mut update: impl FnMut(&mut GameTickPacket, u32, Option<&ControllerState>),
+    ) -> Run {
+        let mut run = Run {
+            controllers: vec![],
+            finished: None,
+        };
+        for tick in 0..ticks {
+            update(&mut self.packet, tick, run.controllers.last());
+            let world = World::from_packet(&self.packet).expect("the packet should parse");
+            if tick == 0 {
+                action.on_start(&world);
+            }
+            let result = action.step(
+                &world,
+                ControllerState::default(),
+                &self.predictions,
+                self.dt,
+            );
+            self.packet.gameInfo.as_mut().unwrap().secondsElapsed += self.dt;
+            match result {
+                ActionResult::InProgress(res) => run.controllers.push(res.controller),
+                ActionResult::Success => {
+                    run.finished = Some(Ok(()));
+                    break;
+                }
+                ActionResult::Failed(reason) => {
+                    run.finished = Some(Err(reason));
+                    break;
+                }
+            }
+        }
+        if let Some(result) = run.finished {
+            action.on_finish(result);
+        }
+        run
+    }
+}
+
+impl Run {
+    pub fn succeeded(&self) -> bool {
+        self.finished == Some(Ok(()))
+    }
+
+    pub fn failed_with(&self, reason: FailureReason) -> bool {
+        self.finished == Some(Err(reason))
+    }
+
+    /// The inputs on tick `n`
+    pub fn tick(&self, n: usize) -> &ControllerState {
+        match self.controllers.get(n) {
+            Some(controller) => controller,
+            None => panic!("only ran for {} ticks", self.controllers.len()),
+        }
+    }
+
+    /// Panics with `what` if `check` doesn't hold for the inputs on every tick
+    pub fn assert_all(&self, what: &str, check: impl Fn(&ControllerState) -> bool) {
+        if let Some(n) = self.controllers.iter().position(|c| !check(c)) {
+            panic!("expected {what} on every tick, but not on tick {n}: {:?}", self.tick(n));
+        }
+    }
+
+    /// Panics with `what` if `check` doesn't hold for the inputs on any tick
+    pub fn assert_any(&self, what: &str, check: impl Fn(&ControllerState) -> bool) {
+        if !self.controllers.iter().any(check) {
+            panic!("expected {what} on some tick, over {} ticks", self.controllers.len());
+        }
+    }
+
+    /// How many ticks in a row, from the first, `check` held for
+    pub fn ticks_while(&self, check: impl Fn(&ControllerState) -> bool) -> usize {
+        self.controllers.iter().take_while(|c| check(c)).count()
+    }
+}
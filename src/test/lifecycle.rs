@@ -0,0 +1,219 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rlbot_lib::rlbot::{ControllerState, DesiredGameState, PredictionSlice, RenderMessage};
+
+use crate::{
+    actions::action::{Action, ActionResult, FailureReason, PreemptCause},
+    bot::bot::Agent,
+    strategies::strategy::{Attempt, Strategy},
+    utils::{world::world::World, ActionTickResult},
+};
+
+use super::packets::tick;
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+/// Runs for a few steps, then fails
+#[derive(Clone, Debug)]
+struct Flaky {
+    steps: u32,
+    log: Log,
+}
+
+impl Action for Flaky {
+    fn step(
+        &mut self,
+        _world: &World,
+        controller: ControllerState,
+        _predictions: &Vec<PredictionSlice>,
+        _dt: f32,
+    ) -> ActionResult {
+        self.steps += 1;
+        if self.steps > 3 {
+            ActionResult::Failed(FailureReason::TargetUnreachable)
+        } else {
+            ActionResult::InProgress(ActionTickResult::from(controller))
+        }
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        vec![]
+    }
+
+    fn interruptible(&self) -> bool {
+        true
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        String::from("Flaky")
+    }
+
+    fn on_start(&mut self, _world: &World) {
+        self.log.borrow_mut().push(String::from("start"));
+    }
+
+    fn on_interrupt(&mut self, cause: PreemptCause) {
+        self.log.borrow_mut().push(format!("interrupt {cause:?}"));
+    }
+
+    fn on_finish(&mut self, result: Result<(), FailureReason>) {
+        self.log.borrow_mut().push(format!("finish {result:?}"));
+    }
+}
+
+/// Never finishes. Its progress goes down by `rate` every step
+#[derive(Clone, Debug)]
+struct Crawl {
+    remaining: f32,
+    rate: f32,
+}
+
+impl Action for Crawl {
+    fn step(
+        &mut self,
+        _world: &World,
+        controller: ControllerState,
+        _predictions: &Vec<PredictionSlice>,
+        _dt: f32,
+    ) -> ActionResult {
+        self.remaining -= self.rate;
+        ActionResult::InProgress(ActionTickResult::from(controller))
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        vec![]
+    }
+
+    fn interruptible(&self) -> bool {
+        true
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        String::from("Crawl")
+    }
+
+    fn expected_duration(&self) -> Option<f32> {
+        Some(4.)
+    }
+
+    fn progress(&self) -> Option<f32> {
+        Some(self.remaining)
+    }
+}
+
+struct Planner {
+    log: Log,
+    /// Crawl at this rate instead of running Flaky
+    crawl: Option<f32>,
+}
+
+impl Strategy for Planner {
+    fn choose_action(
+        &self,
+        _world: &World,
+        _car_id: usize,
+        _ball_predictions: &Vec<PredictionSlice>,
+        _kickoff: bool,
+    ) -> Option<Box<dyn Action>> {
+        match self.crawl {
+            Some(rate) => Some(Box::new(Crawl {
+                remaining: 1000.,
+                rate,
+            })),
+            None => Some(Box::new(Flaky {
+                steps: 0,
+                log: self.log.clone(),
+            })),
+        }
+    }
+
+    fn on_action_finished(&mut self, action: &dyn Action, result: Result<(), FailureReason>) {
+        self.log
+            .borrow_mut()
+            .push(format!("strategy {} {result:?}", action.name()));
+    }
+
+    fn set_game_state(&self, _car_id: usize, _previous: &Attempt) -> Option<DesiredGameState> {
+        None
+    }
+}
+
+fn agent_with(crawl: Option<f32>) -> (Agent, Log) {
+    let log: Log = Rc::new(RefCell::new(vec![]));
+    let strategy = Planner {
+        log: log.clone(),
+        crawl,
+    };
+    (Agent::new(false, 0, Box::new(strategy)), log)
+}
+
+fn agent() -> (Agent, Log) {
+    agent_with(None)
+}
+
+/// Feed the agent the ticks between two game times. The first 20 ticks are ignored
+fn play(agent: &mut Agent, from: f32, to: f32) {
+    for i in (from * 120.) as usize..(to * 120.) as usize {
+        agent.handle_game_tick(tick(i as f32 / 120.), &vec![]);
+    }
+}
+
+#[test]
+fn failures_reach_the_action_and_the_strategy() {
+    let (mut agent, log) = agent();
+    // 20 ignored ticks, then 4 steps until the action fails
+    for i in 0..24 {
+        agent.handle_game_tick(tick(i as f32 / 120.), &vec![]);
+    }
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            "start",
+            "finish Err(TargetUnreachable)",
+            "strategy Flaky Err(TargetUnreachable)",
+        ]
+    );
+}
+
+#[test]
+fn kickoffs_interrupt_other_actions() {
+    let (mut agent, log) = agent();
+    for i in 0..22 {
+        agent.handle_game_tick(tick(i as f32 / 120.), &vec![]);
+    }
+    let mut kickoff = tick(22. / 120.);
+    kickoff.gameInfo.as_mut().unwrap().isKickoffPause = true;
+    agent.handle_game_tick(kickoff, &vec![]);
+
+    let log = log.borrow();
+    assert_eq!(log[1], "interrupt Kickoff");
+    assert_eq!(log[2], "finish Err(Preempted(Kickoff))");
+    assert_eq!(log[3], "strategy Flaky Err(Preempted(Kickoff))");
+    // and a new one gets picked right away
+    assert_eq!(log[4], "start");
+}
+
+#[test]
+fn actions_making_progress_run_until_their_deadline() {
+    let (mut agent, log) = agent_with(Some(1.));
+    play(&mut agent, 0., 5.);
+    // 4 seconds expected, so it's dropped after 6
+    assert!(log.borrow().is_empty());
+    play(&mut agent, 5., 7.);
+    assert_eq!(log.borrow()[0], "strategy Crawl Err(Preempted(Overdue))");
+}
+
+#[test]
+fn stalled_actions_are_dropped() {
+    let (mut agent, log) = agent_with(Some(0.));
+    play(&mut agent, 0., 1.5);
+    assert_eq!(log.borrow()[0], "strategy Crawl Err(Preempted(Stalled))");
+}
@@ -0,0 +1,33 @@
+use std::f32::consts::PI;
+
+use crate::utils::math::math::{vec_new, Vec3};
+
+fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-5, "{a} != {b}");
+}
+
+#[test]
+fn normalize_keeps_the_direction_and_makes_it_length_one() {
+    let v = vec_new(-1., 0., 0.).normalize();
+    assert_eq!((v.x, v.y, v.z), (-1., 0., 0.));
+    let v = vec_new(0., -300., 0.).normalize();
+    assert_eq!((v.x, v.y, v.z), (0., -1., 0.));
+
+    // components that cancel out are fine too
+    let v = vec_new(3., -4., 0.).normalize();
+    assert_close(v.x, 0.6);
+    assert_close(v.y, -0.8);
+    assert_close(v.norm(), 1.);
+}
+
+#[test]
+fn angle_between_stays_in_range() {
+    let x = vec_new(1., 0., 0.);
+    assert_close(x.angle_between(&vec_new(0., 0., 5.)), PI / 2.);
+    assert_close(x.angle_between(&vec_new(-2., 0., 0.)), PI);
+    assert_close(vec_new(1., 1., 0.).angle_between(&vec_new(0., -1., 0.)), 3. * PI / 4.);
+
+    // rounding can put the cosine just past 1, that shouldn't come out as NaN
+    let v = vec_new(0.1, 0.2, 0.3);
+    assert_close(v.angle_between(&v.scale(3.)), 0.);
+}
@@ -0,0 +1,128 @@
+/// A stand-in for the RLBot server, so the connection loop can be tested without the game.
+///
+/// It speaks the same socket protocol as RLBot: every message is a big endian u16 data type, a
+/// big endian u16 payload size, and then the flatbuffer payload.
+pub mod mock_server {
+    use std::{
+        io::{self, Read, Write},
+        net::{SocketAddr, TcpListener, TcpStream},
+        sync::{Arc, Mutex},
+        thread::{self, JoinHandle},
+    };
+
+    use rlbot_lib::{
+        rlbot::{DesiredGameState, PlayerInput, QuickChat, ReadyMessage, RenderGroup},
+        Packet,
+    };
+
+    use crate::utils::wire::wire::{decode, encode};
+
+    /// Everything the bot sent back to us
+    #[derive(Default)]
+    pub struct Received {
+        pub ready: Vec<ReadyMessage>,
+        pub inputs: Vec<PlayerInput>,
+        pub renders: Vec<RenderGroup>,
+        pub quick_chats: Vec<QuickChat>,
+        pub game_states: Vec<DesiredGameState>,
+    }
+
+    pub struct MockServer {
+        addr: SocketAddr,
+        received: Arc<Mutex<Received>>,
+        handle: Option<JoinHandle<()>>,
+    }
+
+    impl MockServer {
+        /// Start listening on a random local port. The script is streamed to the first
+        /// connection once it sends its ReadyMessage
+        pub fn start(script: Vec<Packet>) -> MockServer {
+            let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+            let addr = listener.local_addr().unwrap();
+            let received = Arc::new(Mutex::new(Received::default()));
+
+            let thread_received = received.clone();
+            let handle = thread::spawn(move || {
+                let (stream, _) = listener.accept().expect("accept bot connection");
+                serve(stream, script, thread_received);
+            });
+
+            MockServer {
+                addr,
+                received,
+                handle: Some(handle),
+            }
+        }
+
+        pub fn addr(&self) -> String {
+            self.addr.to_string()
+        }
+
+        /// Wait for the bot to hang up, and hand back everything it sent
+        pub fn finish(mut self) -> Received {
+            if let Some(handle) = self.handle.take() {
+                handle.join().expect("mock server thread panicked");
+            }
+            std::mem::take(&mut *self.received.lock().unwrap())
+        }
+    }
+
+    fn serve(stream: TcpStream, script: Vec<Packet>, received: Arc<Mutex<Received>>) {
+        let mut reader = stream.try_clone().unwrap();
+        let mut writer = stream;
+
+        // the handshake: the bot has to tell us what it wants before we stream anything
+        loop {
+            match read_message(&mut reader).map(|(t, payload)| decode(t, &payload)) {
+                Ok(Ok(Packet::ReadyMessage(ready))) => {
+                    received.lock().unwrap().ready.push(ready);
+                    break;
+                }
+                Ok(_) => continue,
+                Err(_) => return,
+            }
+        }
+
+        let record_received = received.clone();
+        let recorder = thread::spawn(move || {
+            while let Ok((data_type, payload)) = read_message(&mut reader) {
+                record(&mut record_received.lock().unwrap(), data_type, &payload);
+            }
+        });
+
+        for packet in script {
+            let (data_type, payload) = encode(&packet);
+            if write_message(&mut writer, data_type, &payload).is_err() {
+                break;
+            }
+        }
+
+        recorder.join().expect("mock server recorder panicked");
+    }
+
+    fn record(received: &mut Received, data_type: u16, payload: &[u8]) {
+        match decode(data_type, payload).expect("decode packet from the bot") {
+            Packet::PlayerInput(p) => received.inputs.push(p),
+            Packet::RenderGroup(p) => received.renders.push(p),
+            Packet::QuickChat(p) => received.quick_chats.push(p),
+            Packet::DesiredGameState(p) => received.game_states.push(p),
+            _ => {}
+        }
+    }
+
+    fn read_message(stream: &mut TcpStream) -> io::Result<(u16, Vec<u8>)> {
+        let mut header = [0u8; 4];
+        stream.read_exact(&mut header)?;
+        let data_type = u16::from_be_bytes([header[0], header[1]]);
+        let size = u16::from_be_bytes([header[2], header[3]]) as usize;
+        let mut payload = vec![0u8; size];
+        stream.read_exact(&mut payload)?;
+        Ok((data_type, payload))
+    }
+
+    fn write_message(stream: &mut TcpStream, data_type: u16, payload: &[u8]) -> io::Result<()> {
+        stream.write_all(&data_type.to_be_bytes())?;
+        stream.write_all(&(payload.len() as u16).to_be_bytes())?;
+        stream.write_all(payload)
+    }
+}
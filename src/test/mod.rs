@@ -1,2 +1,18 @@
-
-pub mod game_state;
+pub mod actions;
+pub mod arena;
+pub mod behavior_tree;
+pub mod builder;
+pub mod config;
+pub mod connection_loop;
+pub mod dribble;
+pub mod harness;
+pub mod lifecycle;
+pub mod math;
+pub mod mock_server;
+pub mod packets;
+pub mod preemption;
+pub mod registry;
+pub mod replay;
+pub mod resets;
+pub mod script;
+pub mod strike;
@@ -0,0 +1,11 @@
+use rlbot_lib::rlbot::GameTickPacket;
+
+use super::builder::{CarBuilder, PacketBuilder};
+
+/// A packet with one car sitting in front of its goal and the ball at kickoff position
+pub fn tick(seconds_elapsed: f32) -> GameTickPacket {
+    PacketBuilder::new()
+        .time(seconds_elapsed)
+        .car(CarBuilder::new())
+        .build()
+}
@@ -0,0 +1,378 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rlbot_lib::rlbot::{ControllerState, DesiredGameState, PredictionSlice, RenderMessage};
+
+use crate::{
+    actions::{
+        action::{Action, ActionResult, FailureReason},
+        behavior_tree::Sequence,
+    },
+    bot::bot::Agent,
+    strategies::strategy::{Attempt, Candidate, Strategy},
+    utils::{world::world::World, ActionTickResult},
+};
+
+use super::packets::tick;
+
+/// Holds the throttle at `throttle` forever
+#[derive(Clone, Debug)]
+struct Hold {
+    throttle: f32,
+    interruptible: bool,
+}
+
+impl Action for Hold {
+    fn step(
+        &mut self,
+        _world: &World,
+        controller: ControllerState,
+        _predictions: &Vec<PredictionSlice>,
+        _dt: f32,
+    ) -> ActionResult {
+        ActionResult::InProgress(ActionTickResult::from(ControllerState {
+            throttle: self.throttle,
+            ..controller
+        }))
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        vec![]
+    }
+
+    fn interruptible(&self) -> bool {
+        self.interruptible
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        format!("Hold {}", self.throttle)
+    }
+}
+
+/// Never gets any closer to being done
+#[derive(Clone, Debug)]
+struct Stuck;
+
+impl Action for Stuck {
+    fn step(
+        &mut self,
+        _world: &World,
+        controller: ControllerState,
+        _predictions: &Vec<PredictionSlice>,
+        _dt: f32,
+    ) -> ActionResult {
+        ActionResult::InProgress(ActionTickResult::from(controller))
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        vec![]
+    }
+
+    fn interruptible(&self) -> bool {
+        true
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        String::from("Stuck")
+    }
+
+    fn progress(&self) -> Option<f32> {
+        Some(1000.)
+    }
+}
+
+/// Only ever picks `Stuck`, inside a Sequence
+struct Nested {
+    finished: Rc<RefCell<Vec<String>>>,
+}
+
+impl Strategy for Nested {
+    fn choose_action(
+        &self,
+        _world: &World,
+        _car_id: usize,
+        _ball_predictions: &Vec<PredictionSlice>,
+        _kickoff: bool,
+    ) -> Option<Box<dyn Action>> {
+        Some(Box::new(Sequence::new(vec![Box::new(Stuck)])))
+    }
+
+    fn on_action_finished(&mut self, action: &dyn Action, result: Result<(), FailureReason>) {
+        self.finished
+            .borrow_mut()
+            .push(format!("{} {result:?}", action.name()));
+    }
+
+    fn set_game_state(&self, _car_id: usize, _previous: &Attempt) -> Option<DesiredGameState> {
+        None
+    }
+}
+
+/// Offers "forward" (throttle 1) and "reverse" (throttle -1) at whatever utilities the test sets.
+/// A utility below zero leaves that one out
+struct Scored {
+    utilities: Rc<RefCell<(f32, f32)>>,
+    interruptible: bool,
+    finished: Rc<RefCell<Vec<String>>>,
+}
+
+impl Strategy for Scored {
+    fn choose_action(
+        &self,
+        _world: &World,
+        _car_id: usize,
+        _ball_predictions: &Vec<PredictionSlice>,
+        _kickoff: bool,
+    ) -> Option<Box<dyn Action>> {
+        None
+    }
+
+    fn candidates(
+        &self,
+        _world: &World,
+        _car_id: usize,
+        _ball_predictions: &Vec<PredictionSlice>,
+        _kickoff: bool,
+        _current: Option<&dyn Action>,
+    ) -> Vec<Candidate> {
+        let (forward, reverse) = *self.utilities.borrow();
+        let hold = |throttle: f32| -> Box<dyn Action> {
+            Box::new(Hold {
+                throttle,
+                interruptible: self.interruptible,
+            })
+        };
+        vec![
+            Candidate {
+                label: "forward",
+                utility: forward,
+                action: hold(1.),
+            },
+            Candidate {
+                label: "reverse",
+                utility: reverse,
+                action: hold(-1.),
+            },
+        ]
+        .into_iter()
+        .filter(|candidate| candidate.utility >= 0.)
+        .collect()
+    }
+
+    fn on_action_finished(&mut self, action: &dyn Action, result: Result<(), FailureReason>) {
+        self.finished
+            .borrow_mut()
+            .push(format!("{} {result:?}", action.name()));
+    }
+
+    fn set_game_state(&self, _car_id: usize, _previous: &Attempt) -> Option<DesiredGameState> {
+        None
+    }
+}
+
+/// Succeeds after `left` more steps
+#[derive(Clone, Debug)]
+struct Countdown {
+    left: u32,
+}
+
+impl Action for Countdown {
+    fn step(
+        &mut self,
+        _world: &World,
+        controller: ControllerState,
+        _predictions: &Vec<PredictionSlice>,
+        _dt: f32,
+    ) -> ActionResult {
+        if self.left == 0 {
+            return ActionResult::Success;
+        }
+        self.left -= 1;
+        ActionResult::InProgress(ActionTickResult::from(controller))
+    }
+
+    fn render(&self) -> Vec<RenderMessage> {
+        vec![]
+    }
+
+    fn interruptible(&self) -> bool {
+        true
+    }
+
+    fn kickoff(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        String::from("Countdown")
+    }
+}
+
+/// Always offers a `Countdown` from 10. Every tick it forks the running one and steps the copy
+/// to the end, writing down how many steps that took
+struct Forking {
+    forked: Rc<RefCell<Vec<u32>>>,
+    finished: Rc<RefCell<Vec<String>>>,
+}
+
+impl Strategy for Forking {
+    fn choose_action(
+        &self,
+        _world: &World,
+        _car_id: usize,
+        _ball_predictions: &Vec<PredictionSlice>,
+        _kickoff: bool,
+    ) -> Option<Box<dyn Action>> {
+        None
+    }
+
+    fn candidates(
+        &self,
+        world: &World,
+        _car_id: usize,
+        ball_predictions: &Vec<PredictionSlice>,
+        _kickoff: bool,
+        current: Option<&dyn Action>,
+    ) -> Vec<Candidate> {
+        if let Some(current) = current {
+            let mut fork = current.clone_box();
+            let mut steps = 0;
+            let controller = ControllerState::default();
+            while let ActionResult::InProgress(_) =
+                fork.step(world, controller.clone(), ball_predictions, 1. / 120.)
+            {
+                steps += 1;
+            }
+            self.forked.borrow_mut().push(steps);
+        }
+        vec![Candidate {
+            label: "countdown",
+            utility: 0.5,
+            action: Box::new(Countdown { left: 10 }),
+        }]
+    }
+
+    fn on_action_finished(&mut self, action: &dyn Action, result: Result<(), FailureReason>) {
+        self.finished
+            .borrow_mut()
+            .push(format!("{} {result:?}", action.name()));
+    }
+
+    fn set_game_state(&self, _car_id: usize, _previous: &Attempt) -> Option<DesiredGameState> {
+        None
+    }
+}
+
+struct Harness {
+    agent: Agent,
+    utilities: Rc<RefCell<(f32, f32)>>,
+    finished: Rc<RefCell<Vec<String>>>,
+    ticks: usize,
+}
+
+impl Harness {
+    fn new(interruptible: bool) -> Harness {
+        let utilities = Rc::new(RefCell::new((0.5, 0.)));
+        let finished = Rc::new(RefCell::new(vec![]));
+        let strategy = Scored {
+            utilities: utilities.clone(),
+            interruptible,
+            finished: finished.clone(),
+        };
+        let mut harness = Harness {
+            agent: Agent::new(false, 0, Box::new(strategy)),
+            utilities,
+            finished,
+            ticks: 0,
+        };
+        // get past the ticks the agent ignores
+        for _ in 0..20 {
+            harness.throttle();
+        }
+        harness
+    }
+
+    /// Run a tick and return the throttle the agent used
+    fn throttle(&mut self) -> f32 {
+        let res = self.agent.handle_game_tick(tick(self.ticks as f32 / 120.), &vec![]);
+        self.ticks += 1;
+        res.input.controllerState.unwrap().throttle
+    }
+}
+
+#[test]
+fn switches_only_past_the_margin() {
+    let mut harness = Harness::new(true);
+    assert_eq!(harness.throttle(), 1.);
+
+    // better, but not by enough
+    *harness.utilities.borrow_mut() = (0.5, 0.55);
+    assert_eq!(harness.throttle(), 1.);
+
+    *harness.utilities.borrow_mut() = (0.5, 0.7);
+    assert_eq!(harness.throttle(), -1.);
+    assert_eq!(harness.finished.borrow()[0], "Hold 1 Err(Preempted(Outscored))");
+
+    // and it takes the same margin to switch back
+    *harness.utilities.borrow_mut() = (0.75, 0.7);
+    assert_eq!(harness.throttle(), -1.);
+}
+
+#[test]
+fn plans_that_are_no_longer_offered_score_nothing() {
+    let mut harness = Harness::new(true);
+    assert_eq!(harness.throttle(), 1.);
+
+    // "forward" drops out, so anything beats it
+    *harness.utilities.borrow_mut() = (-1., 0.2);
+    assert_eq!(harness.throttle(), -1.);
+    assert_eq!(harness.finished.borrow()[0], "Hold 1 Err(Preempted(Outscored))");
+}
+
+#[test]
+fn uninterruptible_actions_are_kept() {
+    let mut harness = Harness::new(false);
+    assert_eq!(harness.throttle(), 1.);
+    *harness.utilities.borrow_mut() = (0.1, 1.);
+    assert_eq!(harness.throttle(), 1.);
+    assert!(harness.finished.borrow().is_empty());
+}
+
+#[test]
+fn stalled_children_of_a_sequence_are_dropped() {
+    let finished = Rc::new(RefCell::new(vec![]));
+    let strategy = Nested {
+        finished: finished.clone(),
+    };
+    let mut agent = Agent::new(false, 0, Box::new(strategy));
+    // 20 ignored ticks, then a little over a second of getting nowhere
+    for i in 0..160 {
+        agent.handle_game_tick(tick(i as f32 / 120.), &vec![]);
+    }
+    assert_eq!(finished.borrow()[0], "Sequence > Stuck Err(Preempted(Stalled))");
+}
+
+#[test]
+fn strategies_can_fork_the_running_action_without_touching_it() {
+    let forked = Rc::new(RefCell::new(vec![]));
+    let finished = Rc::new(RefCell::new(vec![]));
+    let strategy = Forking {
+        forked: forked.clone(),
+        finished: finished.clone(),
+    };
+    let mut agent = Agent::new(false, 0, Box::new(strategy));
+    // 20 ignored ticks, then the countdown starts and takes one step a tick
+    for i in 0..31 {
+        agent.handle_game_tick(tick(i as f32 / 120.), &vec![]);
+    }
+    // every fork ran to the end, but the real one kept counting down a step at a time
+    assert_eq!(*forked.borrow(), vec![9, 8, 7, 6, 5, 4, 3, 2, 1, 0]);
+    assert_eq!(*finished.borrow(), vec!["Countdown Ok(())"]);
+}
@@ -0,0 +1,37 @@
+use crate::{
+    config::config::{Config, Tuning},
+    strategies::registry::{self, StrategyParams},
+};
+
+fn params(table: &str) -> StrategyParams {
+    StrategyParams {
+        tuning: Tuning::default(),
+        params: table.parse().unwrap(),
+    }
+}
+
+#[test]
+fn every_registered_strategy_builds_with_defaults() {
+    for entry in registry::STRATEGIES {
+        assert!((entry.build)(&params("")).is_ok(), "{}", entry.name);
+    }
+}
+
+#[test]
+fn example_config_params_are_valid() {
+    let mut config = Config::parse(include_str!("../../bot.toml")).unwrap();
+    for entry in registry::STRATEGIES {
+        let params = StrategyParams {
+            tuning: config.tuning.clone(),
+            params: config.strategies.remove(entry.name).unwrap_or_default(),
+        };
+        assert!((entry.build)(&params).is_ok(), "{}", entry.name);
+    }
+}
+
+#[test]
+fn bad_names_and_params_are_errors() {
+    assert!(registry::build("nope", &params("")).is_err());
+    assert!(registry::build("solo", &params("grab_boost = 3")).is_err());
+    assert!(registry::build("solo", &params("grab_bost = false")).is_err());
+}
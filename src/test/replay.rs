@@ -0,0 +1,67 @@
+use std::{env, fs, path::PathBuf};
+
+use rlbot_lib::{rlbot::BallPrediction, Packet};
+
+use crate::{
+    bot::bot::Agent,
+    recorder::recorder::{LogReader, Recorder},
+    replay::replay::replay,
+    session::session::{Backoff, Session},
+    strategies::{solo_strategy::SoloStrategy, test_strategy::TestStrategy},
+};
+
+use super::{mock_server::mock_server::MockServer, packets::tick};
+
+/// Play `ticks` ticks against the mock server with the recorder on
+fn record_match(name: &str, ticks: usize) -> PathBuf {
+    let path = env::temp_dir().join(format!("{name}_{}.rlrec", std::process::id()));
+    let mut script = vec![];
+    for i in 0..ticks {
+        script.push(Packet::BallPrediction(BallPrediction {
+            slices: Some(vec![]),
+        }));
+        script.push(Packet::GameTickPacket(tick(i as f32 / 120.)));
+    }
+    let steps = script.len();
+    let server = MockServer::start(script);
+
+    let agent = Agent::new(false, 0, Box::new(SoloStrategy::default()));
+    let mut session =
+        Session::new(&server.addr(), Backoff::default(), agent).expect("connect to mock server");
+    session.record_to(Recorder::create(&path).expect("create match log"));
+    session.ready().unwrap();
+    for _ in 0..steps {
+        session.step().unwrap();
+    }
+    drop(session);
+    server.finish();
+
+    path
+}
+
+#[test]
+fn same_agent_replays_identically() {
+    let path = record_match("same_agent", 40);
+
+    let mut agent = Agent::new(false, 0, Box::new(SoloStrategy::default()));
+    let report = replay(LogReader::open(&path).unwrap(), &mut agent).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(report.ticks, 40);
+    assert!(report.divergence.is_none());
+}
+
+#[test]
+fn different_strategy_diverges_once_the_agent_wakes_up() {
+    let path = record_match("different_strategy", 40);
+
+    let mut agent = Agent::new(false, 0, Box::new(TestStrategy::default()));
+    let report = replay(LogReader::open(&path).unwrap(), &mut agent).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    // the agent sits still for the first 20 ticks no matter the strategy
+    let divergence = report.divergence.expect("the strategies should disagree");
+    assert_eq!(divergence.tick, 21);
+    assert_eq!(divergence.recorded_action.as_deref(), Some("DriveAction"));
+    assert_eq!(divergence.replayed_action.as_deref(), Some("RecoverAction"));
+}
@@ -0,0 +1,97 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rlbot_lib::rlbot::{DesiredGameState, PredictionSlice, TeamInfo};
+
+use crate::{
+    actions::action::Action,
+    bot::bot::Agent,
+    strategies::strategy::{Attempt, ResetPolicy, ResetTrigger, Strategy},
+    utils::world::world::World,
+};
+
+use super::packets::tick;
+
+/// Does nothing, and writes down every reset it's asked for
+struct Drill {
+    policy: ResetPolicy,
+    attempts: Rc<RefCell<Vec<Attempt>>>,
+}
+
+impl Strategy for Drill {
+    fn choose_action(
+        &self,
+        _world: &World,
+        _car_id: usize,
+        _ball_predictions: &Vec<PredictionSlice>,
+        _kickoff: bool,
+    ) -> Option<Box<dyn Action>> {
+        None
+    }
+
+    fn reset_policy(&self) -> ResetPolicy {
+        self.policy.clone()
+    }
+
+    fn set_game_state(&self, _car_id: usize, previous: &Attempt) -> Option<DesiredGameState> {
+        self.attempts.borrow_mut().push(previous.clone());
+        Some(DesiredGameState::default())
+    }
+}
+
+fn agent(policy: ResetPolicy) -> (Agent, Rc<RefCell<Vec<Attempt>>>) {
+    let attempts = Rc::new(RefCell::new(vec![]));
+    let strategy = Drill {
+        policy,
+        attempts: attempts.clone(),
+    };
+    (Agent::new(false, 0, Box::new(strategy)), attempts)
+}
+
+#[test]
+fn resets_after_the_timeout() {
+    let (mut agent, attempts) = agent(ResetPolicy {
+        timeout: Some(0.5),
+        ..Default::default()
+    });
+    let resets = (0..100)
+        .filter(|i| {
+            let res = agent.handle_game_tick(tick(*i as f32 / 120.), &vec![]);
+            res.game_state.is_some()
+        })
+        .count();
+    assert_eq!(resets, 1);
+    let attempts = attempts.borrow();
+    assert_eq!(attempts[0].number, 0);
+    assert_eq!(attempts[0].trigger, ResetTrigger::Timeout);
+    assert!(attempts[0].duration > 0.5);
+}
+
+#[test]
+fn resets_after_a_goal() {
+    let (mut agent, attempts) = agent(ResetPolicy {
+        on_goal: true,
+        ..Default::default()
+    });
+    for i in 0..60 {
+        let mut packet = tick(i as f32 / 120.);
+        if i >= 40 {
+            packet.teams = Some(vec![TeamInfo {
+                teamIndex: 1,
+                score: 1,
+                ..Default::default()
+            }]);
+        }
+        let res = agent.handle_game_tick(packet, &vec![]);
+        assert_eq!(res.game_state.is_some(), i == 40, "tick {i}");
+    }
+    assert_eq!(attempts.borrow()[0].trigger, ResetTrigger::Goal);
+}
+
+#[test]
+fn never_resets_by_default() {
+    let (mut agent, attempts) = agent(ResetPolicy::default());
+    for i in 0..2000 {
+        agent.handle_game_tick(tick(i as f32 / 120.), &vec![]);
+    }
+    assert!(attempts.borrow().is_empty());
+}
@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use rlbot_lib::rlbot::ControllerState;
+
+use crate::{
+    actions::{
+        action::{Action, ActionResult},
+        script_action::{Script, ScriptAction, ScriptError, Step},
+    },
+    strategies::script_strategy::ScriptStrategy,
+    utils::world::world::World,
+};
+
+use super::packets::tick;
+
+#[test]
+fn example_script_parses() {
+    let script = Script::parse(ScriptStrategy::DEFAULT_SCRIPT).unwrap();
+    assert_eq!(script.name, "kickoff_dodge");
+    assert!(script.kickoff);
+    assert_eq!(script.steps.len(), 3);
+    assert!(matches!(script.steps[1], Step::Dodge { target: Some(_), .. }));
+    assert!(matches!(
+        script.steps[2],
+        Step::Recover {
+            jump_when_upside_down: false
+        }
+    ));
+}
+
+#[test]
+fn bad_scripts_are_errors() {
+    // no such step
+    assert!(Script::parse(r#"(name: "x", steps: [Wavedash()])"#).is_err());
+    // missing the speed
+    assert!(Script::parse(r#"(name: "x", steps: [Drive(target: Ball)])"#).is_err());
+    // typo in a field
+    assert!(Script::parse(r#"(name: "x", steps: [Jump(secnds: 0.2)])"#).is_err());
+    assert!(matches!(
+        Script::load(Path::new("scripts/does_not_exist.ron")),
+        Err(ScriptError::Read(_))
+    ));
+}
+
+#[test]
+fn runs_the_first_step() {
+    let script = Script::parse(ScriptStrategy::DEFAULT_SCRIPT).unwrap();
+    let mut action = ScriptAction::new(&script, 0);
+    let world = World::from_packet(&tick(0.)).unwrap();
+    action.on_start(&world);
+    match action.step(&world, ControllerState::default(), &vec![], 1. / 120.) {
+        ActionResult::InProgress(res) => assert_eq!(res.controller.throttle, 1.),
+        _ => panic!("the drive should still be going"),
+    }
+    assert!(action.name().starts_with("Script kickoff_dodge > "));
+    assert!(action.kickoff());
+}
+
+#[test]
+fn empty_script_succeeds_straight_away() {
+    let script = Script::parse(r#"(name: "nothing", steps: [])"#).unwrap();
+    let mut action = ScriptAction::new(&script, 0);
+    let world = World::from_packet(&tick(0.)).unwrap();
+    assert!(matches!(
+        action.step(&world, ControllerState::default(), &vec![], 1. / 120.),
+        ActionResult::Success
+    ));
+}
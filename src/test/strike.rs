@@ -0,0 +1,88 @@
+use std::f32::consts::PI;
+
+use crate::{
+    actions::strike::{StrikeAction, StrikeKind},
+    utils::{
+        arena::Arena,
+        physics::physics::{jump_height, time_to_rise},
+    },
+};
+
+use super::{
+    builder::{CarBuilder, PacketBuilder, PredictionBuilder},
+    harness::ActionHarness,
+};
+
+#[test]
+fn jumps_rise_then_fall() {
+    let single = jump_height(0.5, false);
+    let double = jump_height(0.5, true);
+    assert!(single > 150. && single < 250.);
+    assert!(double > single + 50.);
+    // past the peak of a single jump
+    assert!(jump_height(1.5, false) < jump_height(0.9, false));
+
+    assert!(time_to_rise(100., false).unwrap() < time_to_rise(200., false).unwrap());
+    assert!(time_to_rise(300., false).is_none());
+    assert!(time_to_rise(300., true).is_some());
+    assert!(time_to_rise(600., true).is_none());
+}
+
+/// The kind `choose` goes for, with the ball hanging still at `z` in front of us
+fn kind_for_ball_at(z: f32, boost: i32) -> Option<StrikeKind> {
+    let world = PacketBuilder::new()
+        .car(CarBuilder::new().facing(PI / 2.).boost(boost))
+        .ball_at(0., -1000., z)
+        .world();
+    let predictions = PredictionBuilder::new()
+        .ball_at(0., -1000., z)
+        .floating()
+        .build();
+    StrikeAction::choose(0, &world, &predictions, None).map(|strike| strike.kind)
+}
+
+#[test]
+fn each_height_gets_its_own_strike() {
+    assert_eq!(kind_for_ball_at(93., 33), Some(StrikeKind::DriveThrough));
+    assert_eq!(kind_for_ball_at(250., 33), Some(StrikeKind::Jump));
+    assert_eq!(kind_for_ball_at(450., 33), Some(StrikeKind::DoubleJump));
+    assert_eq!(kind_for_ball_at(1200., 100), Some(StrikeKind::Aerial));
+    // can't get up there without boost
+    assert_eq!(kind_for_ball_at(1200., 0), None);
+}
+
+#[test]
+fn floor_balls_by_the_back_wall_are_not_jumped_at() {
+    let world = PacketBuilder::new()
+        .car(CarBuilder::new().facing(-PI / 2.))
+        .ball_at(0., -5050., 93.)
+        .world();
+    assert!(world.ball.location.y.abs() > Arena::SIZE.y - 100.);
+    let car = world.car(0).unwrap();
+    for kind in StrikeKind::ALL {
+        assert!(!kind.feasible(car, &world.ball, 2.), "{kind:?}");
+    }
+}
+
+#[test]
+fn strike_succeeds_once_we_touch_the_ball() {
+    let packet = PacketBuilder::new()
+        .time(5.)
+        .car(CarBuilder::new().facing(PI / 2.))
+        // from before we started, doesn't count
+        .touch(0, 0, 1.)
+        .build();
+    let predictions = PredictionBuilder::new().start(5.).build();
+    let mut strike = StrikeAction::new(0, StrikeKind::DriveThrough, None);
+    let mut harness = ActionHarness::new(packet).predictions(predictions);
+
+    let run = harness.run_with(&mut strike, 20, |packet, tick, _| {
+        if tick == 10 {
+            let now = packet.gameInfo.as_ref().unwrap().secondsElapsed;
+            let ball = packet.ball.as_mut().unwrap();
+            ball.latestTouch.as_mut().unwrap().gameSeconds = now;
+        }
+    });
+    assert!(run.succeeded());
+    assert_eq!(run.controllers.len(), 10);
+}
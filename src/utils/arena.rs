@@ -1,9 +1,76 @@
 use rlbot_lib::rlbot::Vector3;
 
-use super::math::math::abs_clamp;
+use super::math::math::{abs_clamp, vec_new, Vec3};
 
 pub struct Arena {}
 
+/// One of the flat sides of the arena, ignoring the rounded corners and seams. The walls carry
+/// the sign of the coordinate they're at
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Surface {
+    Floor,
+    Ceiling,
+    SideWall(f32),
+    BackWall(f32),
+}
+
+impl Surface {
+    /// Points into the arena
+    pub fn normal(&self) -> Vector3 {
+        match self {
+            Surface::Floor => vec_new(0., 0., 1.),
+            Surface::Ceiling => vec_new(0., 0., -1.),
+            Surface::SideWall(sign) => vec_new(-sign, 0., 0.),
+            Surface::BackWall(sign) => vec_new(0., -sign, 0.),
+        }
+    }
+
+    /// How far `pos` is from this surface, into the arena
+    pub fn height(&self, pos: &Vector3) -> f32 {
+        match self {
+            Surface::Floor => pos.z,
+            Surface::Ceiling => Arena::SIZE.z - pos.z,
+            Surface::SideWall(sign) => Arena::SIZE.x - sign * pos.x,
+            Surface::BackWall(sign) => Arena::SIZE.y - sign * pos.y,
+        }
+    }
+
+    /// The surface a car is driving on, from its up vector
+    pub fn from_up(up: &Vector3) -> Surface {
+        if up.z.abs() >= up.x.abs() && up.z.abs() >= up.y.abs() {
+            if up.z > 0. {
+                Surface::Floor
+            } else {
+                Surface::Ceiling
+            }
+        } else if up.x.abs() >= up.y.abs() {
+            Surface::SideWall(-up.x.signum())
+        } else {
+            Surface::BackWall(-up.y.signum())
+        }
+    }
+
+    /// Fold `pos`, on this surface, out flat into the plane of `onto`, like the arena was a
+    /// cardboard box opened up. Driving straight at the result crosses the seam where it should
+    pub fn unfold(&self, pos: &Vector3, onto: Surface) -> Vector3 {
+        if *self == onto {
+            return pos.clone();
+        }
+        let from_normal = self.normal();
+        let onto_normal = onto.normal();
+        if from_normal.dot(&onto_normal) < -0.5 {
+            // opposite sides, so go by way of a surface touching both
+            let between = match self {
+                Surface::Floor | Surface::Ceiling => Surface::SideWall(pos.x.signum()),
+                _ => Surface::Floor,
+            };
+            return between.unfold(&self.unfold(pos, between), onto);
+        }
+        let dist = onto.height(pos);
+        pos.sub(&onto_normal.scale(dist)).sub(&from_normal.scale(dist))
+    }
+}
+
 impl Arena {
     pub const SIZE: Vector3 = Vector3 {
         x: 4096.,
@@ -11,6 +78,12 @@ impl Arena {
         z: 2044.,
     };
 
+    /// Half the width and the height of the opening of each goal
+    pub const GOAL_HALF_WIDTH: f32 = 892.755;
+    pub const GOAL_HEIGHT: f32 = 642.775;
+    /// Anything further than this from every surface is in the air, not something to drive to
+    const MAX_DRIVE_HEIGHT: f32 = 300.;
+
     pub fn clamp(pos: &Vector3, offset: f32) -> Vector3 {
         return Vector3 {
             x: abs_clamp(pos.x, Arena::SIZE.x - offset),
@@ -48,6 +121,35 @@ impl Arena {
         }
     }
 
+    /// The surface `pos` is closest to
+    pub fn surface(pos: &Vector3) -> Surface {
+        [
+            Surface::Floor,
+            Surface::Ceiling,
+            Surface::SideWall(1.),
+            Surface::SideWall(-1.),
+            Surface::BackWall(1.),
+            Surface::BackWall(-1.),
+        ]
+        .into_iter()
+        .min_by(|a, b| {
+            a.height(pos)
+                .partial_cmp(&b.height(pos))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .unwrap()
+    }
+
+    /// Whether a car can get to `pos` by driving, up the walls if need be. Not in the air, and not
+    /// on the back wall where the goal is
+    pub fn drivable(pos: &Vector3) -> bool {
+        let surface = Arena::surface(pos);
+        let in_goal = matches!(surface, Surface::BackWall(_))
+            && pos.x.abs() < Arena::GOAL_HALF_WIDTH
+            && pos.z < Arena::GOAL_HEIGHT;
+        surface.height(pos) < Arena::MAX_DRIVE_HEIGHT && !in_goal
+    }
+
     /// Does this position collide with the wall or the ground? if it does, return the normal of
     /// the collision surface (roughly)
     pub fn collide(pos: &Vector3) -> Option<Vector3> {
@@ -35,15 +35,17 @@ const LOCATIONS: [(f32, f32, f32); 34] = [
     (0.0, 4240.0, 70.0),
 ];
 pub mod pads {
-    use rlbot_lib::rlbot::{GameTickPacket, PlayerInfo, Vector3};
+    use rlbot_lib::rlbot::{GameTickPacket, Vector3};
 
     use crate::utils::{
         intercept::estimate_time,
         math::math::{vec_new, Vec3},
+        world::world::{Car, World},
     };
 
     use super::LOCATIONS;
 
+    #[derive(Clone, Debug)]
     pub struct BoostPad {
         pub is_active: bool,
         pub timer: f32,
@@ -55,11 +57,11 @@ pub mod pads {
             tick_packet
                 .boostPadStates
                 .clone()
-                .unwrap()
+                .unwrap_or_default()
                 .into_iter()
-                .enumerate()
-                .map(|(i, pad)| BoostPad {
-                    location: vec_new(LOCATIONS[i].0, LOCATIONS[i].1, LOCATIONS[i].2),
+                .zip(LOCATIONS.iter())
+                .map(|(pad, (x, y, z))| BoostPad {
+                    location: vec_new(*x, *y, *z),
                     timer: pad.timer,
                     is_active: pad.isActive,
                 })
@@ -74,14 +76,13 @@ pub mod pads {
     }
 
     pub fn choose_boostpad(
-        info: &GameTickPacket,
-        car: PlayerInfo,
+        world: &World,
+        car: &Car,
         my_goal: &Vector3,
         bad_pads: Vec<BoostPad>,
     ) -> Option<BoostPad> {
-        let pads = BoostPad::extract_info(&info);
-        let active_pads = pads.into_iter().filter(|pad| {
-            pad.is_active || estimate_time(&car, pad.location.clone()) * 0.7 > pad.timer
+        let active_pads = world.boost_pads.iter().cloned().filter(|pad| {
+            pad.is_active || estimate_time(car, pad.location.clone()) * 0.7 > pad.timer
         });
 
         let valid_pads = active_pads.filter(|pad| !bad_pads.contains(pad));
@@ -93,15 +94,10 @@ pub mod pads {
         // Choose the pad that's closest to the midpoint of us, the ball, and our goal, weighting
         // our position and the goals position 2x higher than the ball
 
-        let pos = info
+        let pos = world
             .ball
-            .clone()
-            .unwrap()
-            .physics
-            .unwrap()
             .location
-            .unwrap()
-            .add(&car.physics.clone().unwrap().location.unwrap().scale(2.))
+            .add(&car.location.scale(2.))
             .add(&my_goal.scale(2.))
             .scale(0.2);
 
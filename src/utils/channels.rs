@@ -0,0 +1,51 @@
+/// Splitting the controller into groups of inputs, so several actions can drive the car at once
+/// as long as they stick to their own inputs. See `behavior_tree::Blend`.
+
+pub mod channels {
+    use rlbot_lib::rlbot::ControllerState;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Channel {
+        /// steer and throttle
+        Drive,
+        Boost,
+        /// pitch, yaw and roll
+        Rotation,
+        /// jump and handbrake
+        Buttons,
+    }
+
+    pub const ALL: [Channel; 4] = [
+        Channel::Drive,
+        Channel::Boost,
+        Channel::Rotation,
+        Channel::Buttons,
+    ];
+
+    /// Copy the inputs in `channel` from one controller to another
+    pub fn copy(channel: Channel, from: &ControllerState, to: &mut ControllerState) {
+        match channel {
+            Channel::Drive => {
+                to.steer = from.steer;
+                to.throttle = from.throttle;
+            }
+            Channel::Boost => to.boost = from.boost,
+            Channel::Rotation => {
+                to.pitch = from.pitch;
+                to.yaw = from.yaw;
+                to.roll = from.roll;
+            }
+            Channel::Buttons => {
+                to.jump = from.jump;
+                to.handbrake = from.handbrake;
+            }
+        }
+    }
+
+    /// Lay `layer` over `base`, but only on the channels the layer owns
+    pub fn overlay(base: &mut ControllerState, layer: &ControllerState, channels: &[Channel]) {
+        for channel in channels {
+            copy(*channel, layer, base);
+        }
+    }
+}
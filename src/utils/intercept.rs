@@ -1,42 +1,53 @@
-use rlbot_lib::rlbot::{Physics, PlayerInfo, PredictionSlice, Vector3};
+use rlbot_lib::rlbot::{PredictionSlice, Vector3};
 
 use super::{
     arena::Arena,
     math::math::{forward_vec, Vec3},
+    world::world::{Ball, Car},
 };
 
-#[derive(Clone)]
+/// The highest a ball can be and still count as on the ground
+pub const GROUNDED_BALL_HEIGHT: f32 = 200.;
+
+#[derive(Clone, Debug)]
 pub struct Intercept {
-    pub ball: Physics,
-    pub car: PlayerInfo,
+    pub ball: Ball,
+    pub car: Car,
     pub is_viable: bool,
+    /// Our estimate of how long it takes to drive there
     pub time: f32,
+    /// secondsElapsed when the ball gets there
+    pub game_seconds: f32,
     pub location: Vector3,
-    use_predicate: bool,
     pub predicate_later_than_time: bool,
 }
 
 impl Intercept {
     pub fn new(
-        car: &PlayerInfo,
+        car: &Car,
         game_time: f32,
         ball_predictions: &Vec<PredictionSlice>,
-        ball: Physics,
+        ball: &Ball,
         ignore_time_estimate: bool,
-        use_predicate: bool,
+        predicate: Option<&dyn Fn(&Car, &Ball, f32) -> bool>,
     ) -> Intercept {
-        let mut the_ball: Option<Physics> = None;
+        let mut the_ball: Option<Ball> = None;
         let mut is_viable = true;
         let mut time = f32::MAX;
+        let mut game_seconds = game_time;
         let mut predicate_later_than_time = false;
-        for ball in ball_predictions.clone() {
-            let ball_phys = ball.physics.unwrap();
-            let ball_location = ball_phys.location.clone().unwrap();
-            time = estimate_time(car, ball_location);
+        for slice in ball_predictions {
+            let slice_ball = match slice.physics.as_ref().and_then(|p| Ball::from_physics(p)) {
+                Some(b) => b,
+                None => continue,
+            };
+            time = estimate_time(car, slice_ball.location.clone());
+            let time_left = slice.gameSeconds - game_time;
 
-            if time < ball.gameSeconds - game_time || ignore_time_estimate {
-                if !use_predicate || predicate(car.clone(), *ball_phys.clone()) {
-                    the_ball = Some(*ball_phys.clone());
+            if time < time_left || ignore_time_estimate {
+                if predicate.map_or(true, |predicate| predicate(car, &slice_ball, time_left)) {
+                    the_ball = Some(slice_ball);
+                    game_seconds = slice.gameSeconds;
                     break;
                 }
                 predicate_later_than_time = true;
@@ -45,24 +56,27 @@ impl Intercept {
             }
         }
         if the_ball.is_none() {
-            if ball_predictions.len() > 0 {
-                if let Some(last) = ball_predictions.last() {
-                    the_ball = Some(*last.physics.clone().unwrap());
-                } else {
-                    the_ball = Some(ball.clone());
-                }
-            }
+            // fall back to the last slice we have, or the ball as it is right now
+            the_ball = ball_predictions
+                .last()
+                .and_then(|last| {
+                    game_seconds = last.gameSeconds;
+                    last.physics.as_ref()
+                })
+                .and_then(|p| Ball::from_physics(p))
+                .or(Some(ball.clone()));
             is_viable = false;
         }
-        let location = Some(the_ball.clone().unwrap().location.unwrap());
+        let the_ball = the_ball.unwrap();
+        let location = the_ball.location.clone();
 
         Intercept {
-            ball: the_ball.unwrap(),
+            ball: the_ball,
             car: car.clone(),
             is_viable,
             time,
-            location: location.unwrap(),
-            use_predicate,
+            game_seconds,
+            location,
             predicate_later_than_time,
         }
     }
@@ -97,12 +111,10 @@ fn curvature(v: f32) -> f32 {
 
 // This function is ignoring the direction that the car is moving, assuming that it's driving
 // forwards
-pub fn estimate_time(car: &PlayerInfo, target: Vector3) -> f32 {
-    let car_phys = car.physics.clone().unwrap();
-    let car_vel = car_phys.velocity.clone().unwrap();
-    let car_rot = car_phys.rotation.clone().unwrap();
-    let car_loc = car_phys.location.clone().unwrap();
-    let forward = forward_vec(&car_rot);
+pub fn estimate_time(car: &Car, target: Vector3) -> f32 {
+    let car_vel = &car.velocity;
+    let car_loc = &car.location;
+    let forward = forward_vec(&car.rotation);
     let turning_radius = turn_radius(car_vel.norm());
     let mut turning = forward.angle_between(&car_loc.direction(&target)) * turning_radius / 1800.;
     if turning < 0.5 {
@@ -145,11 +157,10 @@ pub fn estimate_time(car: &PlayerInfo, target: Vector3) -> f32 {
     time * 1.05 + turning
 }
 
-// HACK: I'm just passing a boolean to avoid having to pass this function around b/c I can't clone
-// it and I don't have time to figure out how to do that. Rust is hard
-fn predicate(_car: PlayerInfo, ball: Physics) -> bool {
-    let ball_loc = ball.location.clone().unwrap();
-    if ball_loc.z > 200. || ball_loc.y.abs() > Arena::SIZE.y - 100. {
+/// A ball we can hit off the ground, away from the back walls. For `Intercept::new`'s predicate
+pub fn grounded_ball(_car: &Car, ball: &Ball, _time_left: f32) -> bool {
+    let ball_loc = &ball.location;
+    if ball_loc.z > GROUNDED_BALL_HEIGHT || ball_loc.y.abs() > Arena::SIZE.y - 100. {
         false
     } else {
         // Note: this might be too simplistic to work I'm not sure.
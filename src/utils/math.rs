@@ -29,6 +29,11 @@ pub mod math {
         n
     }
 
+    /// The same angle, between -PI and PI
+    pub fn wrap_angle(angle: f32) -> f32 {
+        (angle + PI).rem_euclid(2. * PI) - PI
+    }
+
     // don't ask me why this works. I don't know
     pub fn rotate(
         axis: &nalgebra::Unit<
@@ -73,6 +78,15 @@ pub mod math {
         Vector3::from_nalg(rotate(&nalgebra::Vector3::x_axis(), rotr, rotp, roty))
     }
 
+    /// `v` in the car's own frame: x is forwards, y is to the left and z is up
+    pub fn to_local(v: &Vector3, rotator: &Rotator) -> Vector3 {
+        vec_new(
+            v.dot(&forward_vec(rotator)),
+            v.dot(&left_vec(rotator)),
+            v.dot(&up_vec(rotator)),
+        )
+    }
+
     pub fn dir_vecs(rotator: &Rotator) -> Vec<Vector3> {
         let rotr = nalgebra::Rotation::from_euler_angles(0., rotator.roll, 0.);
         let rotp = nalgebra::Rotation::from_euler_angles(-rotator.pitch, 0., 0.);
@@ -177,12 +191,7 @@ pub mod math {
 
         /// direction vector of length 1
         fn normalize(&self) -> Vector3 {
-            let sum = self.x + self.y + self.z;
-            Vector3 {
-                x: self.x / sum,
-                y: self.y / sum,
-                z: self.z / sum,
-            }
+            self.scale(1. / self.norm())
         }
 
         /// length of the vector
@@ -222,7 +231,7 @@ pub mod math {
         }
 
         fn angle_between(&self, v: &Vector3) -> f32 {
-            (self.dot(v) / (self.norm() * v.norm())).acos()
+            (self.dot(v) / (self.norm() * v.norm())).clamp(-1., 1.).acos()
         }
 
         fn direction(&self, target: &Vector3) -> Vector3 {
@@ -1,11 +1,15 @@
-use rlbot_lib::rlbot::{PlayerInput, RenderMessage, ControllerState};
+use rlbot_lib::rlbot::{PlayerInput, RenderMessage, ControllerState, DesiredGameState};
 
 pub mod rl_match;
 pub mod arena;
 pub mod math;
+pub mod physics;
 pub mod render;
 pub mod intercept;
 pub mod boost;
+pub mod channels;
+pub mod wire;
+pub mod world;
 
 pub struct ActionTickResult {
     pub controller: ControllerState,
@@ -24,6 +28,10 @@ impl ActionTickResult {
 pub struct AgentTickResult {
     pub input: PlayerInput,
     pub render: Vec<RenderMessage>,
+    /// Name of the action that produced the input, if there was one
+    pub action: Option<String>,
+    /// Set when the strategy wants the game reset, see `Strategy::reset_policy`
+    pub game_state: Option<DesiredGameState>,
 }
 
 impl AgentTickResult {
@@ -31,6 +39,8 @@ impl AgentTickResult {
         AgentTickResult {
             input,
             render: vec![],
+            action: None,
+            game_state: None,
         }
     }
 }
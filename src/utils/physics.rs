@@ -0,0 +1,77 @@
+/// Numbers from the game's car physics, and rough models built on them for planning jumps and
+/// aerials. Values are from the RLBot wiki
+pub mod physics {
+    pub const GRAVITY: f32 = -650.;
+    /// Acceleration from holding boost
+    pub const BOOST_ACCEL: f32 = 991.667;
+    /// Boost used per second of holding boost
+    pub const BOOST_PER_SECOND: f32 = 33.3;
+    /// Vertical speed from pressing jump, and again from the second jump
+    pub const JUMP_SPEED: f32 = 291.667;
+    /// Extra upwards acceleration while jump is held, for up to `JUMP_HOLD_TIME`
+    pub const JUMP_HOLD_ACCEL: f32 = 1458.333;
+    pub const JUMP_HOLD_TIME: f32 = 0.2;
+    /// How long after the first jump the car can still dodge or jump again
+    pub const DODGE_WINDOW: f32 = 1.25;
+    /// Height of the car's center when it's sitting on the ground
+    pub const CAR_REST_HEIGHT: f32 = 17.;
+    pub const BALL_RADIUS: f32 = 92.75;
+
+    const DT: f32 = 1. / 120.;
+
+    /// A car that just jumped off flat ground, stepped forward a tick at a time
+    struct Jump {
+        t: f32,
+        height: f32,
+        speed: f32,
+        double_jumped: bool,
+    }
+
+    impl Jump {
+        /// With `double_jump`, the second jump comes right after letting go of the first
+        fn new(double_jump: bool) -> Jump {
+            Jump {
+                t: 0.,
+                height: 0.,
+                speed: JUMP_SPEED,
+                double_jumped: !double_jump,
+            }
+        }
+
+        fn step(&mut self) {
+            let mut accel = GRAVITY;
+            if self.t < JUMP_HOLD_TIME {
+                accel += JUMP_HOLD_ACCEL;
+            } else if !self.double_jumped {
+                self.speed += JUMP_SPEED;
+                self.double_jumped = true;
+            }
+            self.speed += accel * DT;
+            self.height += self.speed * DT;
+            self.t += DT;
+        }
+    }
+
+    /// How far above where it took off the car is `time` seconds after jumping, holding jump for
+    /// as long as it helps
+    pub fn jump_height(time: f32, double_jump: bool) -> f32 {
+        let mut jump = Jump::new(double_jump);
+        while jump.t < time {
+            jump.step();
+        }
+        jump.height
+    }
+
+    /// How long after jumping the car gets `height` above where it took off, on the way up. None
+    /// if the jump never gets that high
+    pub fn time_to_rise(height: f32, double_jump: bool) -> Option<f32> {
+        let mut jump = Jump::new(double_jump);
+        while jump.height < height {
+            if jump.speed < 0. && jump.double_jumped {
+                return None;
+            }
+            jump.step();
+        }
+        Some(jump.t)
+    }
+}
@@ -0,0 +1,84 @@
+/// Converting packets to and from the flatbuffer payloads RLBot sends over the socket. The
+/// recorder uses this to store packets on disk, and the mock server uses it to talk to the bot.
+pub mod wire {
+    use planus::{Builder, ReadAsRoot, WriteAsOffset};
+    use rlbot_lib::{
+        rlbot::{
+            BallPredictionRef, DesiredGameStateRef, FieldInfoRef, GameTickPacketRef,
+            MatchSettingsRef, MessagePacketRef, PlayerInputRef, QuickChatRef, ReadyMessageRef,
+            RenderGroupRef,
+        },
+        Packet,
+    };
+
+    // These are the data type numbers RLBot puts in front of every message
+    pub const GAME_TICK_PACKET: u16 = 1;
+    pub const FIELD_INFO: u16 = 2;
+    pub const MATCH_SETTINGS: u16 = 3;
+    pub const PLAYER_INPUT: u16 = 4;
+    pub const DESIRED_GAME_STATE: u16 = 7;
+    pub const RENDER_GROUP: u16 = 8;
+    pub const QUICK_CHAT: u16 = 9;
+    pub const BALL_PREDICTION: u16 = 10;
+    pub const READY_MESSAGE: u16 = 11;
+    pub const MESSAGE_PACKET: u16 = 12;
+
+    #[derive(Debug)]
+    pub enum WireError {
+        UnknownDataType(u16),
+        Decode(planus::Error),
+    }
+
+    impl From<planus::Error> for WireError {
+        fn from(e: planus::Error) -> WireError {
+            WireError::Decode(e)
+        }
+    }
+
+    fn write<T: WriteAsOffset<T>>(value: &T) -> Vec<u8> {
+        Builder::new().finish(value, None).to_vec()
+    }
+
+    fn read<'a, R, T>(payload: &'a [u8]) -> Result<T, planus::Error>
+    where
+        R: ReadAsRoot<'a>,
+        T: TryFrom<R, Error = planus::Error>,
+    {
+        T::try_from(R::read_as_root(payload)?)
+    }
+
+    /// The data type and the flatbuffer payload for a packet
+    pub fn encode(packet: &Packet) -> (u16, Vec<u8>) {
+        match packet {
+            Packet::GameTickPacket(p) => (GAME_TICK_PACKET, write(p)),
+            Packet::FieldInfo(p) => (FIELD_INFO, write(p)),
+            Packet::MatchSettings(p) => (MATCH_SETTINGS, write(p)),
+            Packet::PlayerInput(p) => (PLAYER_INPUT, write(p)),
+            Packet::DesiredGameState(p) => (DESIRED_GAME_STATE, write(p)),
+            Packet::RenderGroup(p) => (RENDER_GROUP, write(p)),
+            Packet::QuickChat(p) => (QUICK_CHAT, write(p)),
+            Packet::BallPrediction(p) => (BALL_PREDICTION, write(p)),
+            Packet::ReadyMessage(p) => (READY_MESSAGE, write(p)),
+            Packet::MessagePacket(p) => (MESSAGE_PACKET, write(p)),
+        }
+    }
+
+    pub fn decode(data_type: u16, payload: &[u8]) -> Result<Packet, WireError> {
+        let packet = match data_type {
+            GAME_TICK_PACKET => Packet::GameTickPacket(read::<GameTickPacketRef, _>(payload)?),
+            FIELD_INFO => Packet::FieldInfo(read::<FieldInfoRef, _>(payload)?),
+            MATCH_SETTINGS => Packet::MatchSettings(read::<MatchSettingsRef, _>(payload)?),
+            PLAYER_INPUT => Packet::PlayerInput(read::<PlayerInputRef, _>(payload)?),
+            DESIRED_GAME_STATE => {
+                Packet::DesiredGameState(read::<DesiredGameStateRef, _>(payload)?)
+            }
+            RENDER_GROUP => Packet::RenderGroup(read::<RenderGroupRef, _>(payload)?),
+            QUICK_CHAT => Packet::QuickChat(read::<QuickChatRef, _>(payload)?),
+            BALL_PREDICTION => Packet::BallPrediction(read::<BallPredictionRef, _>(payload)?),
+            READY_MESSAGE => Packet::ReadyMessage(read::<ReadyMessageRef, _>(payload)?),
+            MESSAGE_PACKET => Packet::MessagePacket(read::<MessagePacketRef, _>(payload)?),
+            other => return Err(WireError::UnknownDataType(other)),
+        };
+        Ok(packet)
+    }
+}
@@ -0,0 +1,180 @@
+/// A typed snapshot of the game, parsed once per tick from the GameTickPacket. Actions and
+/// strategies read from this instead of digging through the packet's Options themselves.
+pub mod world {
+    use rlbot_lib::rlbot::{GameTickPacket, Physics, PlayerInfo, Rotator, Vector3};
+
+    use crate::utils::boost::pads::BoostPad;
+
+    #[derive(Clone, Debug)]
+    pub struct World {
+        pub game: GameInfo,
+        pub ball: Ball,
+        /// Every car we could parse, in packet order. Use `car` to look one up by index, because
+        /// a car with missing physics is skipped
+        pub cars: Vec<Car>,
+        pub boost_pads: Vec<BoostPad>,
+    }
+
+    #[derive(Clone, Debug, Default)]
+    pub struct GameInfo {
+        pub seconds_elapsed: f32,
+        pub game_time_remaining: f32,
+        pub is_overtime: bool,
+        pub is_round_active: bool,
+        pub is_kickoff_pause: bool,
+        pub is_match_ended: bool,
+        pub frame_num: i32,
+        /// Goals scored by blue and orange
+        pub score: [i32; 2],
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct Car {
+        /// Index of this car in the packet, this is what `PlayerInput.playerIndex` refers to
+        pub index: usize,
+        pub name: String,
+        pub team: i32,
+        pub boost: i32,
+        pub location: Vector3,
+        pub velocity: Vector3,
+        pub angular_velocity: Vector3,
+        pub rotation: Rotator,
+        pub has_wheel_contact: bool,
+        pub jumped: bool,
+        pub double_jumped: bool,
+        pub is_supersonic: bool,
+        pub is_demolished: bool,
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct Ball {
+        pub location: Vector3,
+        pub velocity: Vector3,
+        pub angular_velocity: Vector3,
+        pub latest_touch: Option<Touch>,
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct Touch {
+        pub player_name: String,
+        pub player_index: usize,
+        pub team: i32,
+        pub game_seconds: f32,
+        pub location: Vector3,
+    }
+
+    /// Pull the parts of a Physics object we care about out of their Options
+    fn parse_physics(phys: &Physics) -> Option<(Vector3, Vector3, Vector3, Rotator)> {
+        Some((
+            phys.location.clone()?,
+            phys.velocity.clone()?,
+            phys.angularVelocity.clone().unwrap_or_default(),
+            phys.rotation.clone().unwrap_or_default(),
+        ))
+    }
+
+    impl World {
+        /// Parse the tick packet. Returns None when the packet is missing the game info or the
+        /// ball, which happens in the first few ticks and between matches
+        pub fn from_packet(packet: &GameTickPacket) -> Option<World> {
+            let game_info = packet.gameInfo.as_ref()?;
+            let ball_info = packet.ball.as_ref()?;
+
+            let mut game = GameInfo {
+                seconds_elapsed: game_info.secondsElapsed,
+                game_time_remaining: game_info.gameTimeRemaining,
+                is_overtime: game_info.isOvertime,
+                is_round_active: game_info.isRoundActive,
+                is_kickoff_pause: game_info.isKickoffPause,
+                is_match_ended: game_info.isMatchEnded,
+                frame_num: game_info.frameNum,
+                score: [0, 0],
+            };
+            for team in packet.teams.iter().flatten() {
+                if let Some(score) = game.score.get_mut(team.teamIndex.max(0) as usize) {
+                    *score = team.score;
+                }
+            }
+
+            let (location, velocity, angular_velocity, _) =
+                parse_physics(ball_info.physics.as_ref()?)?;
+            let latest_touch = ball_info.latestTouch.as_ref().map(|touch| Touch {
+                player_name: touch.playerName.clone().unwrap_or_default(),
+                player_index: touch.playerIndex.max(0) as usize,
+                team: touch.team,
+                game_seconds: touch.gameSeconds,
+                location: touch.location.clone().unwrap_or_default(),
+            });
+            let ball = Ball {
+                location,
+                velocity,
+                angular_velocity,
+                latest_touch,
+            };
+
+            let cars = packet
+                .players
+                .as_ref()
+                .map(|players| {
+                    players
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(index, player)| Car::from_player(index, player))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            Some(World {
+                game,
+                ball,
+                cars,
+                boost_pads: BoostPad::extract_info(packet),
+            })
+        }
+
+        /// Find a car by its index in the packet
+        pub fn car(&self, index: usize) -> Option<&Car> {
+            self.cars.iter().find(|car| car.index == index)
+        }
+
+        /// Every car that isn't on `team`
+        pub fn opponents(&self, team: i32) -> impl Iterator<Item = &Car> {
+            self.cars.iter().filter(move |car| car.team != team)
+        }
+    }
+
+    impl Car {
+        pub fn from_player(index: usize, player: &PlayerInfo) -> Option<Car> {
+            let (location, velocity, angular_velocity, rotation) =
+                parse_physics(player.physics.as_ref()?)?;
+            Some(Car {
+                index,
+                name: player.name.clone().unwrap_or_default(),
+                team: player.team,
+                boost: player.boost,
+                location,
+                velocity,
+                angular_velocity,
+                rotation,
+                has_wheel_contact: player.hasWheelContact,
+                jumped: player.jumped,
+                double_jumped: player.doubleJumped,
+                is_supersonic: player.isSupersonic,
+                is_demolished: player.isDemolished,
+            })
+        }
+    }
+
+    impl Ball {
+        /// Build a ball from a physics object, like the ones in the ball prediction slices
+        pub fn from_physics(phys: &Physics) -> Option<Ball> {
+            let (location, velocity, angular_velocity, _) = parse_physics(phys)?;
+            Some(Ball {
+                location,
+                velocity,
+                angular_velocity,
+                latest_touch: None,
+            })
+        }
+    }
+}